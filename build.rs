@@ -32,10 +32,20 @@ fn generate_manpages() -> Result<()> {
         ManPage::new("repo-config", ConfigCmd::command()),
         ManPage::new("repo-edit", EditCmd::command()),
         ManPage::new("repo-foreach", ForeachCmd::command()),
+        ManPage::new("repo-cache", CacheCmd::command()),
+        ManPage::new("repo-cache-gc", CacheGcCmd::command()),
+        ManPage::new("repo-hooks", HooksCmd::command()),
+        ManPage::new("repo-hooks-run", HooksRunCmd::command()),
+        ManPage::new("repo-import", ImportCmd::command()),
         ManPage::new("repo-inspect", InspectCmd::command()),
         ManPage::new("repo-init", InitCmd::command()),
         ManPage::new("repo-list", ListCmd::command()),
+        ManPage::new("repo-manifest", ManifestCmd::command()),
+        ManPage::new("repo-manifest-apply", ManifestApplyCmd::command()),
+        ManPage::new("repo-manifest-export", ManifestExportCmd::command()),
+        ManPage::new("repo-prune-branches", PruneBranchesCmd::command()),
         ManPage::new("repo-remove", RemoveCmd::command()),
+        ManPage::new("repo-status", StatusCmd::command()),
         ManPage::new("repo-tag", TagCmd::command()),
         ManPage::new("repo-tag-add", TagAddCmd::command()),
         ManPage::new("repo-tag-edit", TagEditCmd::command()),
@@ -1,17 +1,17 @@
 use crate::{config::Config, util, Location, Repository, Tag};
-use anyhow::{anyhow, Context, Result};
+use crate::{error::Context, Error, Result};
 use std::{
     collections::HashMap,
     io::Write,
     path::{Path, PathBuf},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Cache {
     data: CacheData,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct CacheData {
     repositories: HashMap<String, Repository>,
     tags: HashMap<String, Tag>,
@@ -78,19 +78,27 @@ impl Cache {
                 self.data.repositories.remove(name);
                 Ok(())
             }
-            None => Err(anyhow!("Repository: '{}' is not tracked by repo", name)),
+            None => Err(Error::RepositoryNotFound {
+                name: name.to_owned(),
+                suggestions: Vec::new(),
+            }),
         }
     }
 
     pub fn remove_tag(&mut self, name: &str) -> Result<()> {
         match self.get_tag(name) {
-            Some(tag) => std::fs::remove_file(&tag.config)
-                .context(format!(
+            Some(tag) => {
+                std::fs::remove_file(&tag.config).context(format!(
                     "failed to remove tag config file: {:#?}",
                     &tag.config
-                ))
-                .map_err(Into::into),
-            None => Err(anyhow!("Tag: '{}' is not in repo", name)),
+                ))?;
+                self.data.tags.remove(name);
+                Ok(())
+            }
+            None => Err(Error::TagNotFound {
+                name: name.to_owned(),
+                suggestions: Vec::new(),
+            }),
         }
     }
 
@@ -1,18 +1,16 @@
 use super::{AddCmd, Run};
 use anyhow::{anyhow, bail, Result};
 use repo_cli::prelude::*;
+use std::path::PathBuf;
 
 impl Run for AddCmd {
     fn run(self) -> Result<()> {
         let mut workspace = Workspace::new()?;
 
-        let name = self.name.as_deref().unwrap_or_else(|| {
-            self.url
-                .rsplit('/')
-                .next()
-                .map(|s| s.trim_end_matches(".git"))
-                .unwrap()
-        });
+        let query = Query::parse(&self.url)?;
+        let url = query.to_url(workspace.config())?;
+        let layout = query.layout(workspace.config())?;
+        let name = self.name.as_deref().unwrap_or(&layout.name);
 
         let location = if self.local {
             Location::Local
@@ -31,8 +29,15 @@ impl Run for AddCmd {
             }
         }
 
-        let query = Query::parse(&self.url)?;
-        let url = query.to_url(workspace.config());
+        // NOTE: The group path of the host is only a default, a tag with a path decides the folder
+        let tag_path = self.tags.iter().flatten().any(|name| {
+            workspace
+                .cache()
+                .get_tag(name)
+                .and_then(|tag| tag.path.as_ref())
+                .is_some()
+        });
+        let layout_path = if tag_path { None } else { layout.path };
 
         let mut builder = RepositoryBuilder::new(name)
             .remote(Remote::new(url))
@@ -44,6 +49,12 @@ impl Run for AddCmd {
             }
         }
 
+        if let Some(aliases) = self.aliases {
+            for alias in aliases {
+                builder = builder.alias(alias);
+            }
+        }
+
         if let Some(remotes) = self.remotes {
             for arg in remotes {
                 let split: Vec<&str> = arg.splitn(2, ',').collect();
@@ -56,15 +67,55 @@ impl Run for AddCmd {
                 }
 
                 let q = Query::parse(split[1])?;
-                let remote = Remote::with_name(split[0], q.to_url(workspace.config()));
+                let remote = Remote::with_name(split[0], q.to_url(workspace.config())?);
                 builder = builder.remote(remote);
             }
         }
 
-        if let Some(path) = self.path {
+        if let Some(path) = self.path.map(PathBuf::from).or(layout_path) {
             builder = builder.path(path);
         }
 
+        if let Some(branch) = self.branch {
+            builder = builder.branch(branch);
+        }
+
+        if let Some(depth) = self.depth {
+            builder = builder.depth(depth);
+        }
+
+        if let Some(filter) = self.filter {
+            builder = builder.filter(filter);
+        }
+
+        if let Some(sparse) = self.sparse {
+            for path in sparse {
+                builder = builder.sparse(path);
+            }
+        }
+
+        if self.submodules {
+            builder = builder.submodules(self.submodules);
+        }
+
+        if self.lfs {
+            builder = builder.lfs(self.lfs);
+        }
+
+        if let Some(strategy) = self.update_strategy {
+            builder = builder.update_strategy(strategy.parse()?);
+        }
+
+        if self.autostash {
+            builder = builder.autostash(self.autostash);
+        }
+
+        if let Some(credentials) = self.credentials {
+            for provider in credentials {
+                builder = builder.credential(provider.parse()?);
+            }
+        }
+
         if let Some(clone) = self.clone {
             builder = builder.clone(clone);
         }
@@ -77,6 +128,11 @@ impl Run for AddCmd {
             builder = builder.cli(self.cli);
         }
 
-        workspace.add_repository(builder.build())
+        if let Some(backend) = self.backend {
+            builder = builder.backend(backend.parse()?);
+        }
+
+        workspace.add_repository(builder.build())?;
+        Ok(())
     }
 }
@@ -0,0 +1,39 @@
+use anyhow::bail;
+use repo_cli::Workspace;
+
+use crate::cmd::{CacheGcCmd, Run};
+
+impl Run for CacheGcCmd {
+    fn run(self) -> anyhow::Result<()> {
+        let workspace = Workspace::new()?;
+        let gc = workspace.gc_mirrors(self.dry_run)?;
+        let removed = gc.removed;
+
+        let action = if self.dry_run {
+            "Would remove"
+        } else {
+            "Removed"
+        };
+
+        for path in &removed {
+            println!("{}: {}", action, path.display());
+        }
+
+        if removed.is_empty() && gc.failed.is_empty() {
+            println!("No unused mirrors");
+        }
+
+        for (path, e) in &gc.failed {
+            eprintln!("Failed to detach: {}: {:#}", path.display(), e);
+        }
+
+        if !gc.failed.is_empty() {
+            bail!(
+                "{} clones still use unused mirrors, their mirrors have been kept",
+                gc.failed.len()
+            );
+        }
+
+        Ok(())
+    }
+}
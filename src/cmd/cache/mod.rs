@@ -0,0 +1,11 @@
+use super::{CacheCmd, Run};
+
+impl Run for CacheCmd {
+    fn run(self) -> anyhow::Result<()> {
+        match self.cmd {
+            super::CacheSubCmd::Gc(cmd) => cmd.run(),
+        }
+    }
+}
+
+mod gc;
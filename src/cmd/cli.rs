@@ -20,13 +20,19 @@ pub struct Cli {
 #[derive(Debug, Subcommand)]
 pub enum Cmd {
     Add(AddCmd),
+    Cache(CacheCmd),
     Config(ConfigCmd),
     Edit(EditCmd),
     Foreach(ForeachCmd),
+    Hooks(HooksCmd),
+    Import(ImportCmd),
     Inspect(InspectCmd),
     Init(InitCmd),
     List(ListCmd),
+    Manifest(ManifestCmd),
+    PruneBranches(PruneBranchesCmd),
     Remove(RemoveCmd),
+    Status(StatusCmd),
     Tag(TagCmd),
     Update(UpdateCmd),
     Work(WorkCmd),
@@ -73,6 +79,13 @@ pub struct AddCmd {
     #[arg(short, long, verbatim_doc_comment, default_value = None)]
     pub tags: Option<Vec<String>>,
 
+    /// Add an alias that can be used instead of the repository's name
+    ///
+    /// Commands that take a repository name also accept an alias, a unique
+    /// prefix of a name or alias, or a fuzzy match of one.
+    #[arg(short = 'A', long = "alias", verbatim_doc_comment, default_value = None)]
+    pub aliases: Option<Vec<String>>,
+
     /// Add an additional remote
     ///
     /// Remote will be appended to the repository as an additional remote This
@@ -110,6 +123,68 @@ pub struct AddCmd {
     #[arg(short, long, verbatim_doc_comment, default_value = None)]
     pub path: Option<String>,
 
+    /// Branch to check out when the repository is cloned
+    ///
+    /// By default the branch that the remote's HEAD points to is used. If a
+    /// repository also has a branch definition it will override a tag's.
+    #[arg(short, long, verbatim_doc_comment, default_value = None)]
+    pub branch: Option<String>,
+
+    /// Number of commits to fetch when the repository is cloned
+    ///
+    /// Creates a shallow clone. Use 'update --unshallow' to fetch the
+    /// complete history later. A repository's depth overrides a tag's.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub depth: Option<u32>,
+
+    /// Partial clone filter used when the repository is cloned
+    ///
+    /// Objects that are filtered out, for example with 'blob:none', are
+    /// downloaded on demand. Requires the git cli, which is used for the
+    /// clone even if libgit2 is configured.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub filter: Option<String>,
+
+    /// Path to check out with a sparse checkout, can be repeated
+    ///
+    /// Only the given directories are checked out into the working tree.
+    /// Requires the git cli, which is used for the clone even if libgit2
+    /// is configured.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub sparse: Option<Vec<String>>,
+
+    /// Initialize and update submodules recursively on clone and update
+    #[arg(long, default_value_t = false)]
+    pub submodules: bool,
+
+    /// Download git lfs objects on clone and update
+    ///
+    /// Requires git-lfs to be installed. The git cli is used for lfs even
+    /// if libgit2 is configured.
+    #[arg(long, verbatim_doc_comment, default_value_t = false)]
+    pub lfs: bool,
+
+    /// How the repository is brought up to date on update
+    ///
+    /// 'fetch' only fetches, 'ff-only' fast-forwards, 'rebase' rebases
+    /// local commits onto upstream and 'reset-hard' discards local
+    /// changes. A repository's strategy overrides a tag's.
+    #[arg(long, verbatim_doc_comment, value_parser = ["fetch", "ff-only", "rebase", "reset-hard"])]
+    pub update_strategy: Option<String>,
+
+    /// Stash local changes while updating the branch
+    #[arg(long, default_value_t = false)]
+    pub autostash: bool,
+
+    /// Credential providers libgit2 tries in order
+    ///
+    /// 'ssh-key' and 'token' use the 'ssh_key' and 'token_env' of the host
+    /// alias matching the remote. 'default' tries the ssh-agent, default
+    /// ssh keys and credential helpers. A repository's providers override
+    /// a tag's.
+    #[arg(long, verbatim_doc_comment, value_delimiter = ',', value_parser = ["ssh-agent", "ssh-key", "helper", "token", "default"])]
+    pub credentials: Option<Vec<String>>,
+
     /// Write repository to local cache
     ///
     /// Local cache is defined by '$REPO_LOCAL_PATH' environment variable.
@@ -131,8 +206,18 @@ pub struct AddCmd {
     /// specify a repository to use the command line instead of libgit2. This
     /// mainly happens because of authentication issues If you can get the
     /// command line to clone the repository the repo will use that instead.
+    /// Credential providers can be configured with '--credentials' instead.
     #[arg(short = 'u', long, verbatim_doc_comment, default_value_t = false)]
     pub cli: bool,
+
+    /// Backend used to interact with git
+    ///
+    /// 'libgit' uses libgit2 and 'cli' the git command line. 'auto' uses
+    /// libgit2 and retries with the git command line when libgit2 cannot
+    /// authenticate or does not support the remote's protocol. The backend
+    /// that worked is remembered for the next updates. Overrides '--cli'.
+    #[arg(long, verbatim_doc_comment, value_parser = ["libgit", "cli", "auto"])]
+    pub backend: Option<String>,
 }
 
 /// Get or set configuration options
@@ -203,6 +288,68 @@ pub struct EditCmd {
     #[arg(short, long, verbatim_doc_comment, default_value = None)]
     pub path: Option<String>,
 
+    /// Branch to check out when the repository is cloned
+    ///
+    /// By default the branch that the remote's HEAD points to is used. If a
+    /// repository also has a branch definition it will override a tag's.
+    #[arg(short, long, verbatim_doc_comment, default_value = None)]
+    pub branch: Option<String>,
+
+    /// Number of commits to fetch when the repository is cloned
+    ///
+    /// Creates a shallow clone. Use 'update --unshallow' to fetch the
+    /// complete history later. A repository's depth overrides a tag's.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub depth: Option<u32>,
+
+    /// Partial clone filter used when the repository is cloned
+    ///
+    /// Objects that are filtered out, for example with 'blob:none', are
+    /// downloaded on demand. Requires the git cli, which is used for the
+    /// clone even if libgit2 is configured.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub filter: Option<String>,
+
+    /// Path to check out with a sparse checkout, can be repeated
+    ///
+    /// Only the given directories are checked out into the working tree.
+    /// Requires the git cli, which is used for the clone even if libgit2
+    /// is configured.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub sparse: Option<Vec<String>>,
+
+    /// Initialize and update submodules recursively on clone and update
+    #[arg(long, default_value_t = false)]
+    pub submodules: bool,
+
+    /// Download git lfs objects on clone and update
+    ///
+    /// Requires git-lfs to be installed. The git cli is used for lfs even
+    /// if libgit2 is configured.
+    #[arg(long, verbatim_doc_comment, default_value_t = false)]
+    pub lfs: bool,
+
+    /// How the repository is brought up to date on update
+    ///
+    /// 'fetch' only fetches, 'ff-only' fast-forwards, 'rebase' rebases
+    /// local commits onto upstream and 'reset-hard' discards local
+    /// changes. A repository's strategy overrides a tag's.
+    #[arg(long, verbatim_doc_comment, value_parser = ["fetch", "ff-only", "rebase", "reset-hard"])]
+    pub update_strategy: Option<String>,
+
+    /// Stash local changes while updating the branch
+    #[arg(long, default_value_t = false)]
+    pub autostash: bool,
+
+    /// Credential providers libgit2 tries in order
+    ///
+    /// 'ssh-key' and 'token' use the 'ssh_key' and 'token_env' of the host
+    /// alias matching the remote. 'default' tries the ssh-agent, default
+    /// ssh keys and credential helpers. A repository's providers override
+    /// a tag's.
+    #[arg(long, verbatim_doc_comment, value_delimiter = ',', value_parser = ["ssh-agent", "ssh-key", "helper", "token", "default"])]
+    pub credentials: Option<Vec<String>>,
+
     /// Execute command after being cloned by the update command
     ///
     /// If this repository contains links to tags that also contain 'clone'
@@ -227,6 +374,13 @@ pub struct EditCmd {
     #[arg(short, long, verbatim_doc_comment, default_value = None)]
     pub tags: Option<Vec<String>>,
 
+    /// Add an alias that can be used instead of the repository's name
+    ///
+    /// Commands that take a repository name also accept an alias, a unique
+    /// prefix of a name or alias, or a fuzzy match of one.
+    #[arg(short = 'A', long = "alias", verbatim_doc_comment, default_value = None)]
+    pub aliases: Option<Vec<String>>,
+
     /// Add an additional remote
     ///
     /// Remote will be appended to the repository as an additional remote This
@@ -255,8 +409,18 @@ pub struct EditCmd {
     /// specify a repository to use the command line instead of libgit2. This
     /// mainly happens because of authentication issues If you can get the
     /// command line to clone the repository the repo will use that instead.
+    /// Credential providers can be configured with '--credentials' instead.
     #[arg(short = 'u', long, verbatim_doc_comment, default_value = None)]
     pub cli: bool,
+
+    /// Backend used to interact with git
+    ///
+    /// 'libgit' uses libgit2 and 'cli' the git command line. 'auto' uses
+    /// libgit2 and retries with the git command line when libgit2 cannot
+    /// authenticate or does not support the remote's protocol. The backend
+    /// that worked is remembered for the next updates. Overrides '--cli'.
+    #[arg(long, verbatim_doc_comment, value_parser = ["libgit", "cli", "auto"])]
+    pub backend: Option<String>,
 }
 
 /// Execute command for every tracked repository
@@ -286,6 +450,127 @@ pub struct ForeachCmd {
     /// Perform operation on all repositories, global and local
     #[arg(short, long, default_value_t = false)]
     pub all: bool,
+
+    /// Continue with the remaining repositories when a command fails
+    ///
+    /// The exit code of every failed repository is reported once all
+    /// repositories have been visited.
+    #[arg(short, long, verbatim_doc_comment, default_value_t = false)]
+    pub keep_going: bool,
+
+    /// Number of repositories to execute the command in concurrently
+    #[arg(short, long, default_value_t = 1)]
+    pub jobs: usize,
+
+    /// Group the output of each repository
+    ///
+    /// Instead of printing each line as it is written the output of a
+    /// repository is collected and printed once the command has finished.
+    /// Useful with '--jobs' to avoid interleaved output.
+    #[arg(short = 'G', long, verbatim_doc_comment, default_value_t = false)]
+    pub group: bool,
+}
+
+/// Manage the mirror cache that clones borrow objects from
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo cache",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct CacheCmd {
+    #[clap(subcommand)]
+    pub cmd: CacheSubCmd,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheSubCmd {
+    Gc(CacheGcCmd),
+}
+
+/// Remove mirrors that are not used by any repository
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo cache gc",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct CacheGcCmd {
+    /// Print the mirrors that would be removed without removing them
+    ///
+    /// Clones borrow objects from the mirrors of their remotes. A mirror
+    /// is only unused once no tracked repository has a remote with its
+    /// url, so clones of repositories removed from repo can break.
+    #[arg(short = 'n', long, verbatim_doc_comment)]
+    pub dry_run: bool,
+}
+
+/// Manage repository hooks
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo hooks",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct HooksCmd {
+    #[clap(subcommand)]
+    pub cmd: HooksSubCmd,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HooksSubCmd {
+    Run(HooksRunCmd),
+}
+
+/// Execute the hooks of a repository and its tags
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo hooks run",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct HooksRunCmd {
+    /// Name of the repository
+    #[arg()]
+    pub name: String,
+
+    /// Execute the 'clone' hooks
+    ///
+    /// This is the default if no hook type is specified. Running the clone
+    /// hooks also records if they completed, so that 'update' no longer
+    /// tries to execute them again.
+    #[arg(short, long, verbatim_doc_comment, conflicts_with = "work")]
+    pub clone: bool,
+
+    /// Execute the 'work' hooks
+    #[arg(short, long, conflicts_with = "clone")]
+    pub work: bool,
+}
+
+/// Track existing git repositories found in a directory
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo import",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct ImportCmd {
+    /// Directory that will be searched for git repositories, clones outside of the workspace
+    /// root are skipped
+    #[arg()]
+    pub dir: String,
+
+    /// Add a tag to every imported repository
+    #[arg(short, long, default_value = None)]
+    pub tags: Option<Vec<String>>,
+
+    /// Write repositories to local cache
+    #[arg(short, long, default_value_t = false)]
+    pub local: bool,
+
+    /// Only print the repositories that would be imported
+    #[arg(short = 'n', long, default_value_t = false)]
+    pub dry_run: bool,
 }
 
 /// Prints the shell function used to integrate repo with shell
@@ -344,6 +629,68 @@ pub struct ListCmd {
     pub all: bool,
 }
 
+/// Share a workspace definition as a single manifest file
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo manifest",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct ManifestCmd {
+    #[clap(subcommand)]
+    pub cmd: ManifestSubCmd,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ManifestSubCmd {
+    Apply(ManifestApplyCmd),
+    Export(ManifestExportCmd),
+}
+
+/// Write all repositories and tags into a manifest
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo manifest export",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct ManifestExportCmd {
+    /// Format of the manifest
+    #[arg(short, long, value_parser = ["toml", "json"], default_value = "toml")]
+    pub format: String,
+
+    /// Write the manifest to a file instead of stdout
+    #[arg(short, long, default_value = None)]
+    pub output: Option<String>,
+}
+
+/// Create, update and remove repositories and tags to match a manifest
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo manifest apply",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct ManifestApplyCmd {
+    /// Path to the manifest file
+    ///
+    /// Files ending in '.json' are read as json, everything else as toml.
+    #[arg(verbatim_doc_comment)]
+    pub file: String,
+
+    /// Remove repositories and tags that are not defined in the manifest
+    #[arg(short, long, default_value_t = false)]
+    pub prune: bool,
+
+    /// Only print the changes that would be made
+    #[arg(short = 'n', long, default_value_t = false)]
+    pub dry_run: bool,
+
+    /// Apply the changes without a confirmation prompt
+    #[arg(short, long, default_value_t = false)]
+    pub yes: bool,
+}
+
 /// Remove a repository tracked by repo
 #[derive(Debug, Parser)]
 #[command(
@@ -357,6 +704,74 @@ pub struct RemoveCmd {
     pub names: Vec<String>,
 
     /// Force removal of repository without a conformation prompt.
+    ///
+    /// A name that only matches a repository by prefix or fuzzy match is still confirmed.
+    #[arg(short, long, default_value_t = false)]
+    pub force: bool,
+}
+
+/// Show the git state of tracked repositories
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo status",
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct StatusCmd {
+    /// Show repositories that contain a tag
+    #[arg(short, long, number_of_values = 1, default_value = None)]
+    pub tags: Option<Vec<String>>,
+
+    /// Show only local repositories
+    #[arg(short, long, default_value_t = false)]
+    pub local: bool,
+
+    /// Show only global repositories
+    #[arg(short, long, default_value_t = false)]
+    pub global: bool,
+
+    /// Show all repositories regardless of config filters
+    #[arg(short, long, default_value_t = false)]
+    pub all: bool,
+}
+
+/// Delete local branches whose upstream no longer exists
+///
+/// Remote-tracking branches are pruned by 'repo update --all-branches'.
+/// The checked out branch and branches with commits that are not merged into
+/// it are never deleted.
+#[derive(Debug, Parser)]
+#[command(
+    name = "repo prune-branches",
+    verbatim_doc_comment,
+    disable_colored_help(true),
+    disable_version_flag(true)
+)]
+pub struct PruneBranchesCmd {
+    /// Names of the repositories, all repositories if none are given
+    pub names: Vec<String>,
+
+    /// Perform operation on only repositories that contain tag
+    #[arg(short, long, number_of_values = 1, default_value = None)]
+    pub tags: Option<Vec<String>>,
+
+    /// Perform operation on only local repositories
+    #[arg(short, long, default_value_t = false)]
+    pub local: bool,
+
+    /// Perform operation on only global repositories
+    #[arg(short, long, default_value_t = false)]
+    pub global: bool,
+
+    /// Perform operation on all repositories, global and local
+    #[arg(short, long, default_value_t = false)]
+    pub all: bool,
+
+    /// Show the branches that would be deleted without deleting them
+    #[arg(short = 'n', long, default_value_t = false)]
+    pub dry_run: bool,
+
+    /// Delete the branches without a confirmation prompt
     #[arg(short, long, default_value_t = false)]
     pub force: bool,
 }
@@ -384,6 +799,44 @@ pub struct UpdateCmd {
     /// Perform operation on only repositories that contain tag
     #[arg(short, long, default_value = None)]
     pub tags: Option<Vec<String>>,
+
+    /// Number of repositories to update concurrently
+    ///
+    /// If not defined the 'jobs' option in the configuration is used, which
+    /// defaults to the number of available cpus.
+    #[arg(short, long, verbatim_doc_comment, default_value = None)]
+    pub jobs: Option<usize>,
+
+    /// Fetch the complete history of shallow clones
+    ///
+    /// Repositories that are not cloned yet are cloned in full, ignoring
+    /// their 'depth'.
+    #[arg(long, verbatim_doc_comment, default_value_t = false)]
+    pub unshallow: bool,
+
+    /// Update strategy used for this run
+    ///
+    /// Overrides the strategy of repositories, tags and the config.
+    #[arg(long, verbatim_doc_comment, value_parser = ["fetch", "ff-only", "rebase", "reset-hard"])]
+    pub strategy: Option<String>,
+
+    /// Stash local changes while updating the branch
+    #[arg(long, default_value_t = false)]
+    pub autostash: bool,
+
+    /// Fast-forward every local branch that tracks an upstream
+    ///
+    /// Remote-tracking branches that no longer exist on the remote are
+    /// pruned and local branches whose upstream is gone are reported. Use
+    /// 'repo prune-branches' to delete them.
+    #[arg(long, verbatim_doc_comment, default_value_t = false)]
+    pub all_branches: bool,
+
+    /// Remove git remotes that are not in the repository's remotes
+    ///
+    /// Missing remotes are always added and changed urls always updated.
+    #[arg(long, verbatim_doc_comment, default_value_t = false)]
+    pub prune_remotes: bool,
 }
 
 /// Generate work command for a repostory
@@ -403,6 +856,13 @@ pub struct WorkCmd {
     /// This will not run the after 'work' post hook.
     #[arg(short, long, verbatim_doc_comment, default_value_t = false)]
     pub quick: bool,
+
+    /// Print the resolved 'work' hooks without executing them
+    ///
+    /// The repository's hook is listed first followed by the hooks of its
+    /// tags, ordered by priority.
+    #[arg(short, long, verbatim_doc_comment, default_value_t = false)]
+    pub list: bool,
 }
 
 /// Manage tags
@@ -447,6 +907,68 @@ pub struct TagAddCmd {
     #[arg(short, long, verbatim_doc_comment, default_value = None)]
     pub path: Option<String>,
 
+    /// Branch to check out when an attached repository is cloned
+    ///
+    /// By default the branch that the remote's HEAD points to is used. If a
+    /// repository also has a branch definition it will override a tag's.
+    #[arg(short, long, verbatim_doc_comment, default_value = None)]
+    pub branch: Option<String>,
+
+    /// Number of commits to fetch when the repository is cloned
+    ///
+    /// Creates a shallow clone. Use 'update --unshallow' to fetch the
+    /// complete history later. A repository's depth overrides a tag's.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub depth: Option<u32>,
+
+    /// Partial clone filter used when the repository is cloned
+    ///
+    /// Objects that are filtered out, for example with 'blob:none', are
+    /// downloaded on demand. Requires the git cli, which is used for the
+    /// clone even if libgit2 is configured.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub filter: Option<String>,
+
+    /// Path to check out with a sparse checkout, can be repeated
+    ///
+    /// Only the given directories are checked out into the working tree.
+    /// Requires the git cli, which is used for the clone even if libgit2
+    /// is configured.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub sparse: Option<Vec<String>>,
+
+    /// Initialize and update submodules recursively on clone and update
+    #[arg(long, default_value_t = false)]
+    pub submodules: bool,
+
+    /// Download git lfs objects on clone and update
+    ///
+    /// Requires git-lfs to be installed. The git cli is used for lfs even
+    /// if libgit2 is configured.
+    #[arg(long, verbatim_doc_comment, default_value_t = false)]
+    pub lfs: bool,
+
+    /// How the repository is brought up to date on update
+    ///
+    /// 'fetch' only fetches, 'ff-only' fast-forwards, 'rebase' rebases
+    /// local commits onto upstream and 'reset-hard' discards local
+    /// changes. A repository's strategy overrides a tag's.
+    #[arg(long, verbatim_doc_comment, value_parser = ["fetch", "ff-only", "rebase", "reset-hard"])]
+    pub update_strategy: Option<String>,
+
+    /// Stash local changes while updating the branch
+    #[arg(long, default_value_t = false)]
+    pub autostash: bool,
+
+    /// Credential providers libgit2 tries in order
+    ///
+    /// 'ssh-key' and 'token' use the 'ssh_key' and 'token_env' of the host
+    /// alias matching the remote. 'default' tries the ssh-agent, default
+    /// ssh keys and credential helpers. A repository's providers override
+    /// a tag's.
+    #[arg(long, verbatim_doc_comment, value_delimiter = ',', value_parser = ["ssh-agent", "ssh-key", "helper", "token", "default"])]
+    pub credentials: Option<Vec<String>>,
+
     /// Execute command after being cloned by the update command
     ///
     /// If a repository contains links to tags that also contain 'clone' actions
@@ -503,6 +1025,68 @@ pub struct TagEditCmd {
     #[arg(short, long, verbatim_doc_comment, default_value = None)]
     pub path: Option<String>,
 
+    /// Branch to check out when an attached repository is cloned
+    ///
+    /// By default the branch that the remote's HEAD points to is used. If a
+    /// repository also has a branch definition it will override a tag's.
+    #[arg(short, long, verbatim_doc_comment, default_value = None)]
+    pub branch: Option<String>,
+
+    /// Number of commits to fetch when the repository is cloned
+    ///
+    /// Creates a shallow clone. Use 'update --unshallow' to fetch the
+    /// complete history later. A repository's depth overrides a tag's.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub depth: Option<u32>,
+
+    /// Partial clone filter used when the repository is cloned
+    ///
+    /// Objects that are filtered out, for example with 'blob:none', are
+    /// downloaded on demand. Requires the git cli, which is used for the
+    /// clone even if libgit2 is configured.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub filter: Option<String>,
+
+    /// Path to check out with a sparse checkout, can be repeated
+    ///
+    /// Only the given directories are checked out into the working tree.
+    /// Requires the git cli, which is used for the clone even if libgit2
+    /// is configured.
+    #[arg(long, verbatim_doc_comment, default_value = None)]
+    pub sparse: Option<Vec<String>>,
+
+    /// Initialize and update submodules recursively on clone and update
+    #[arg(long, default_value_t = false)]
+    pub submodules: bool,
+
+    /// Download git lfs objects on clone and update
+    ///
+    /// Requires git-lfs to be installed. The git cli is used for lfs even
+    /// if libgit2 is configured.
+    #[arg(long, verbatim_doc_comment, default_value_t = false)]
+    pub lfs: bool,
+
+    /// How the repository is brought up to date on update
+    ///
+    /// 'fetch' only fetches, 'ff-only' fast-forwards, 'rebase' rebases
+    /// local commits onto upstream and 'reset-hard' discards local
+    /// changes. A repository's strategy overrides a tag's.
+    #[arg(long, verbatim_doc_comment, value_parser = ["fetch", "ff-only", "rebase", "reset-hard"])]
+    pub update_strategy: Option<String>,
+
+    /// Stash local changes while updating the branch
+    #[arg(long, default_value_t = false)]
+    pub autostash: bool,
+
+    /// Credential providers libgit2 tries in order
+    ///
+    /// 'ssh-key' and 'token' use the 'ssh_key' and 'token_env' of the host
+    /// alias matching the remote. 'default' tries the ssh-agent, default
+    /// ssh keys and credential helpers. A repository's providers override
+    /// a tag's.
+    #[arg(long, verbatim_doc_comment, value_delimiter = ',', value_parser = ["ssh-agent", "ssh-key", "helper", "token", "default"])]
+    pub credentials: Option<Vec<String>>,
+
     /// Execute command after being cloned by the update command.
     ///
     /// If a repository contains links to tags that also contain 'clone' actions
@@ -571,6 +1155,8 @@ pub struct TagRemoveCmd {
     pub names: Vec<String>,
 
     /// Force removal tag without a conformation prompt.
+    ///
+    /// A name that only matches a tag by prefix or fuzzy match is still confirmed.
     #[arg(short, long, help = "Force removal of tag", default_value_t = false)]
     pub force: bool,
 }
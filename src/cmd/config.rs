@@ -44,12 +44,31 @@ impl Run for ConfigCmd {
             let options: Vec<(&str, String)> = vec![
                 ("root", format!("{}", config.root(location).display())),
                 ("cli", config.cli(location).to_string()),
+                ("backend", config.backend(location).to_string()),
+                ("jobs", config.jobs(location).to_string()),
+                (
+                    "connect_timeout",
+                    config.connect_timeout(location).to_string(),
+                ),
+                ("timeout", config.timeout(location).to_string()),
+                ("retries", config.retries(location).to_string()),
+                ("strategy", config.update_strategy(location).to_string()),
+                ("autostash", config.autostash(location).to_string()),
+                ("credentials", join(config.credentials(location))),
+                (
+                    "mirror",
+                    config
+                        .mirror(location)
+                        .map(|p| format!("{}", p.display()))
+                        .unwrap_or_default(),
+                ),
                 ("host", config.host(location).to_owned()),
                 ("ssh", config.ssh_user(location).to_owned()),
                 ("scheme", format!("{}", config.scheme(location))),
                 ("shell", config.shell(location).join(" ")),
                 ("include", format!("{:#?}", config.include_tags(location))),
                 ("exclude", format!("{:#?}", config.exclude_tags(location))),
+                ("hosts", format!("{:#?}", host_aliases(config, location))),
             ];
 
             if self.name_only {
@@ -87,6 +106,19 @@ impl ConfigCmd {
         match name {
             "root" => println!("{}", config.root(location).display()),
             "cli" => println!("{}", config.cli(location)),
+            "backend" => println!("{}", config.backend(location)),
+            "jobs" => println!("{}", config.jobs(location)),
+            "connect_timeout" => println!("{}", config.connect_timeout(location)),
+            "timeout" => println!("{}", config.timeout(location)),
+            "retries" => println!("{}", config.retries(location)),
+            "strategy" => println!("{}", config.update_strategy(location)),
+            "autostash" => println!("{}", config.autostash(location)),
+            "credentials" => println!("{}", join(config.credentials(location))),
+            "mirror" => {
+                if let Some(mirror) = config.mirror(location) {
+                    println!("{}", mirror.display());
+                }
+            }
             "host" => println!("{}", config.host(location)),
             "ssh" => println!("{}", config.ssh_user(location)),
             "scheme" => println!("{}", config.scheme(location)),
@@ -101,6 +133,11 @@ impl ConfigCmd {
                     println!("{}", exclude);
                 }
             }
+            "hosts" => {
+                for host in host_aliases(config, location) {
+                    println!("{}", host);
+                }
+            }
             _ => {
                 eprintln!("Unknown configuration option: '{}'", name);
                 std::process::exit(1);
@@ -118,6 +155,28 @@ impl ConfigCmd {
         match name {
             "root" => config.set_root(value, PathBuf::from_str(value)?, location),
             "cli" => config.set_cli(value.parse()?, location),
+            "backend" => config.set_backend(value.parse()?, location),
+            "jobs" => config.set_jobs(value.parse()?, location),
+            "connect_timeout" => config.set_connect_timeout(value.parse()?, location),
+            "timeout" => config.set_timeout(value.parse()?, location),
+            "retries" => config.set_retries(value.parse()?, location),
+            "strategy" => config.set_update_strategy(value.parse()?, location),
+            "autostash" => config.set_autostash(value.parse()?, location),
+            "credentials" => config.set_credentials(
+                value
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|s| !s.is_empty())
+                    .map(str::parse)
+                    .collect::<repo_cli::Result<_>>()?,
+                location,
+            ),
+            "mirror" => {
+                if self.remove {
+                    config.set_mirror(None, location);
+                } else {
+                    config.set_mirror(Some((value, PathBuf::from_str(value)?)), location);
+                }
+            }
             "host" => config.set_host(value, location),
             "ssh" => config.set_ssh(value, location),
             "scheme" => {
@@ -148,12 +207,51 @@ impl ConfigCmd {
                     std::process::exit(1);
                 }
             }
+            "hosts" => {
+                eprintln!("Host aliases are tables, use 'repo config --edit' to change them");
+                std::process::exit(1);
+            }
             _ => {
                 eprintln!("Unknown configuration option: '{}'", name);
                 std::process::exit(1);
             }
         };
 
-        config.write(location)
+        config.write(location)?;
+        Ok(())
     }
 }
+
+fn host_aliases(config: &Config, location: Option<Location>) -> Vec<String> {
+    config
+        .host_aliases(location)
+        .into_iter()
+        .map(|(name, alias)| {
+            let scheme = alias.scheme.unwrap_or_else(|| config.scheme(None));
+            let template = alias.template.as_deref().unwrap_or("{path}");
+            let user = match scheme {
+                Scheme::Ssh => format!(
+                    "{}@",
+                    alias.ssh_user.as_deref().unwrap_or(config.ssh_user(None))
+                ),
+                _ => String::new(),
+            };
+            format!(
+                "{}: {}{}{}/{}",
+                name,
+                scheme.to_url_scheme(),
+                user,
+                alias.host,
+                template
+            )
+        })
+        .collect()
+}
+
+fn join(providers: &[CredentialProvider]) -> String {
+    providers
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -3,20 +3,67 @@ use std::path::PathBuf;
 use anyhow::{anyhow, bail, Result};
 use repo_cli::{prelude::*, util};
 
-use super::{EditCmd, Run};
+use super::{confirm_match, EditCmd, Run};
 
 impl Run for EditCmd {
     fn run(self) -> Result<()> {
         let mut workspace = Workspace::new()?;
 
+        let (repository, exact) = workspace.resolve_repository(&self.name)?;
+        let name = repository.name.clone();
+        if !confirm_match(&self.name, &name, exact)? {
+            return Ok(());
+        }
+
         let mut repository = workspace
-            .take_repository(&self.name)
-            .ok_or_else(|| anyhow!("Repository: '{}' is not tracked by repo", &self.name))?;
+            .take_repository(&name)
+            .ok_or_else(|| anyhow!("Repository: '{}' is not tracked by repo", &name))?;
 
         if self.path.is_some() {
             repository.path = self.path.map(PathBuf::from);
         }
 
+        if self.branch.is_some() {
+            repository.branch = self.branch;
+        }
+
+        if self.depth.is_some() {
+            repository.depth = self.depth;
+        }
+
+        if self.filter.is_some() {
+            repository.filter = self.filter;
+        }
+
+        if self.sparse.is_some() {
+            repository.sparse = self.sparse;
+        }
+
+        if self.submodules {
+            repository.submodules = Some(self.submodules);
+        }
+
+        if self.lfs {
+            repository.lfs = Some(self.lfs);
+        }
+
+        if let Some(strategy) = self.update_strategy {
+            repository.update_strategy = Some(strategy.parse()?);
+        }
+
+        if self.autostash {
+            repository.autostash = Some(self.autostash);
+        }
+
+        if let Some(credentials) = self.credentials {
+            repository.credentials = Some(
+                credentials
+                    .iter()
+                    .map(|p| p.parse())
+                    .collect::<repo_cli::Result<_>>()?,
+            );
+        }
+
         if self.clone.is_some() {
             repository.clone = self.clone;
         }
@@ -29,12 +76,23 @@ impl Run for EditCmd {
             repository.use_cli = Some(self.cli);
         }
 
+        if let Some(backend) = self.backend {
+            repository.backend = Some(backend.parse()?);
+        }
+
         if let Some(tags) = self.tags {
             for tag in tags {
                 repository.tags.insert(tag);
             }
         }
 
+        if let Some(aliases) = self.aliases {
+            for alias in aliases {
+                repository.aliases.insert(alias);
+            }
+            workspace.check_aliases(&repository)?;
+        }
+
         if let Some(remotes) = self.remotes {
             for arg in remotes {
                 let split: Vec<&str> = arg.splitn(2, ',').collect();
@@ -47,7 +105,7 @@ impl Run for EditCmd {
                 }
 
                 let q = Query::parse(split[1])?;
-                let remote = Remote::with_name(split[0], q.to_url(workspace.config()));
+                let remote = Remote::with_name(split[0], q.to_url(workspace.config())?);
                 if repository.remotes.iter().any(|r| r.name == remote.name) {
                     bail!("remote name: {} already exists in repository", remote.name);
                 }
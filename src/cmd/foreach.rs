@@ -1,6 +1,13 @@
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, bail, Context};
 use log::{trace, warn};
-use repo_cli::{prelude::*, util::process};
+use repo_cli::{
+    prelude::*,
+    util::{self, process},
+};
+use std::{
+    io::{self, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use super::{ForeachCmd, Run};
 
@@ -23,7 +30,7 @@ impl Run for ForeachCmd {
             _ => workspace.repositories(),
         };
 
-        if let Some(tags) = self.tags {
+        if let Some(tags) = &self.tags {
             repositories = repositories
                 .into_iter()
                 .filter(|r| tags.iter().any(|t| r.tags.contains(t)))
@@ -49,38 +56,88 @@ impl Run for ForeachCmd {
         })?;
         let rest: &[&str] = shell.split_at(1).1;
 
+        let stop = AtomicBool::new(false);
         let workspace_root = workspace.config().root(None);
-        for repository in repositories {
+        let results = util::parallel_map(&repositories, self.jobs, |repository| {
             let cwd = workspace_root.join(repository.resolve_workspace_path(workspace.cache()));
             let name = repository.name.as_str();
 
+            if stop.load(Ordering::SeqCst) {
+                return None;
+            }
+
             if !cwd.is_dir() {
                 warn!("skipping as '{}' has not been cloned", &name);
-                continue;
+                return None;
             }
 
             let cmd = self.cmd.to_owned();
             trace!("exec: '{}' in: {:#?}", cmd, cwd);
             let mut command = process::piped(program);
-            let status = process::execute_command(
-                command
-                    .args(rest)
-                    .arg(&cmd)
-                    .current_dir(&cwd)
-                    .env("REPO_NAME", name),
-                name.to_owned(),
-            )
+            command
+                .args(rest)
+                .arg(&cmd)
+                .current_dir(&cwd)
+                .env("REPO_NAME", name);
+
+            let status = if self.group {
+                process::execute_command_grouped(&mut command, name.to_owned()).map(
+                    |(status, output)| {
+                        let mut stdout = io::stdout().lock();
+                        _ = stdout.write_all(output.as_bytes());
+                        _ = stdout.flush();
+                        status
+                    },
+                )
+            } else {
+                process::execute_command(&mut command, name.to_owned())
+            }
             .context(format!(
                 "executing cmd: '{} {} {}' at '{}' failed",
                 program,
                 rest.join(" "),
                 cmd,
                 cwd.display()
-            ))?;
+            ));
+
+            if !self.keep_going && !matches!(&status, Ok(status) if status.success()) {
+                stop.store(true, Ordering::SeqCst);
+            }
+
+            Some(status)
+        });
 
-            if !status.success() {
+        let mut failed = Vec::new();
+        for (repository, result) in repositories.iter().zip(results) {
+            let failure = match result {
+                None => continue,
+                Some(Ok(status)) if status.success() => continue,
+                Some(Ok(status)) => match status.code() {
+                    Some(code) => format!("exit code: {}", code),
+                    None => "terminated by signal".to_owned(),
+                },
+                Some(Err(e)) if self.keep_going => format!("{:#}", e),
+                Some(Err(e)) => return Err(e),
+            };
+
+            if !self.keep_going {
                 return Err(anyhow!("External command failed: {}", self.cmd));
             }
+
+            failed.push((repository.name.as_str(), failure));
+        }
+
+        if !failed.is_empty() {
+            println!();
+            for (name, failure) in &failed {
+                println!("{:>20.20} | {}", name, failure);
+            }
+
+            bail!(
+                "External command failed in {} repositories: {}",
+                failed.len(),
+                self.cmd
+            );
         }
 
         Ok(())
@@ -0,0 +1,11 @@
+use super::{HooksCmd, Run};
+
+impl Run for HooksCmd {
+    fn run(self) -> anyhow::Result<()> {
+        match self.cmd {
+            super::HooksSubCmd::Run(cmd) => cmd.run(),
+        }
+    }
+}
+
+mod run;
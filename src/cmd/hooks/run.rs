@@ -0,0 +1,21 @@
+use repo_cli::Workspace;
+
+use crate::cmd::{confirm_match, HooksRunCmd, Run};
+
+impl Run for HooksRunCmd {
+    fn run(self) -> anyhow::Result<()> {
+        let workspace = Workspace::new()?;
+        let (repository, exact) = workspace.resolve_repository(&self.name)?;
+        if !confirm_match(&self.name, &repository.name, exact)? {
+            return Ok(());
+        }
+
+        if self.work {
+            workspace.run_hooks(repository, &repository.work_hooks(workspace.cache()))?;
+        } else {
+            workspace.run_clone_hooks(repository)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use repo_cli::{find_repositories, prelude::*, util, Error};
+use std::collections::HashSet;
+
+use super::{ImportCmd, Run};
+
+impl Run for ImportCmd {
+    fn run(self) -> Result<()> {
+        let mut workspace = Workspace::new()?;
+
+        let location = if self.local {
+            Location::Local
+        } else {
+            Location::Global
+        };
+
+        let dir = util::make_path_buf(&self.dir)?;
+        // NOTE: Clones in different folders can share a name, only the first one is imported
+        let mut imported = HashSet::new();
+        for path in find_repositories(&dir)? {
+            let builder = match workspace.repository_from_clone(&path) {
+                Ok(builder) => builder,
+                Err(Error::OutsideRoot { .. }) => {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    println!("{:>20.20} | skipped: outside of the workspace root", name);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut builder = builder.location(location);
+            if let Some(tags) = &self.tags {
+                for tag in tags {
+                    builder = builder.tag(tag.to_owned());
+                }
+            }
+
+            let repository = builder.build();
+            let name = repository.name.clone();
+
+            if workspace.has_repository(&name) || imported.contains(&name) {
+                println!("{:>20.20} | skipped: already tracked by repo", name);
+                continue;
+            }
+
+            if repository.remotes.is_empty() {
+                println!("{:>20.20} | skipped: no remotes", name);
+                continue;
+            }
+
+            let target = workspace
+                .config()
+                .root(None)
+                .join(repository.resolve_workspace_path(workspace.cache()));
+
+            imported.insert(name.clone());
+            if self.dry_run {
+                println!("{:>20.20} | would import: {}", name, target.display());
+                continue;
+            }
+
+            workspace.add_repository(repository)?;
+            println!("{:>20.20} | imported: {}", name, target.display());
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::{anyhow, bail};
+use anyhow::bail;
 use repo_cli::{util, Workspace};
 
 use super::{InspectCmd, Run};
@@ -6,9 +6,7 @@ use super::{InspectCmd, Run};
 impl Run for InspectCmd {
     fn run(self) -> anyhow::Result<()> {
         let workspace = Workspace::new()?;
-        let repository = workspace
-            .get_repository(&self.name)
-            .ok_or_else(|| anyhow!("Repository: '{}' is not tracked by repo", &self.name))?;
+        let (repository, _) = workspace.resolve_repository(&self.name)?;
 
         if let Some(format) = self.format {
             let ser = match format.as_str() {
@@ -50,6 +48,43 @@ impl Run for InspectCmd {
             println!("{:<15}: {}", "Path", path.display());
         }
 
+        if let Some(branch) = &repository.branch {
+            println!("{:<15}: {}", "Branch", branch);
+        }
+
+        if let Some(depth) = &repository.depth {
+            println!("{:<15}: {}", "Depth", depth);
+        }
+
+        if let Some(filter) = &repository.filter {
+            println!("{:<15}: {}", "Filter", filter);
+        }
+
+        if let Some(sparse) = &repository.sparse {
+            println!("{:<15}: {}", "Sparse", sparse.join(", "));
+        }
+
+        if let Some(submodules) = &repository.submodules {
+            println!("{:<15}: {}", "Submodules", submodules);
+        }
+
+        if let Some(lfs) = &repository.lfs {
+            println!("{:<15}: {}", "Lfs", lfs);
+        }
+
+        if let Some(strategy) = &repository.update_strategy {
+            println!("{:<15}: {}", "Strategy", strategy);
+        }
+
+        if let Some(autostash) = &repository.autostash {
+            println!("{:<15}: {}", "Autostash", autostash);
+        }
+
+        if let Some(credentials) = &repository.credentials {
+            let credentials: Vec<String> = credentials.iter().map(|p| p.to_string()).collect();
+            println!("{:<15}: {}", "Credentials", credentials.join(", "));
+        }
+
         if let Some(clone) = &repository.clone {
             println!("{:<15}: {}", "Clone", clone);
         }
@@ -59,8 +94,21 @@ impl Run for InspectCmd {
         }
 
         println!("{:<15}: {}", "Use cli", use_cli);
+        if let Some(backend) = &repository.backend {
+            println!("{:<15}: {}", "Backend", backend);
+        }
         println!("{:<15}: {}", "Tags", tags);
 
+        if !repository.aliases.is_empty() {
+            let aliases = repository
+                .aliases
+                .iter()
+                .map(|a| a.as_ref())
+                .collect::<Vec<&str>>()
+                .join(", ");
+            println!("{:<15}: {}", "Aliases", aliases);
+        }
+
         let mut first = true;
         for remote in &repository.remotes {
             let output = format!("{}, {}", remote.name, remote.url.as_str());
@@ -0,0 +1,92 @@
+use dialoguer::Confirm;
+use repo_cli::{util, Change, Manifest, ManifestFormat, Workspace};
+use serde::Serialize;
+
+use crate::cmd::{ManifestApplyCmd, Run};
+
+impl Run for ManifestApplyCmd {
+    fn run(self) -> anyhow::Result<()> {
+        let mut workspace = Workspace::new()?;
+
+        let file = util::make_path_buf(&self.file)?;
+        let format = match file.extension().and_then(|e| e.to_str()) {
+            Some("json") => ManifestFormat::Json,
+            _ => ManifestFormat::Toml,
+        };
+
+        let content = util::read_content(&file)?;
+        let manifest = Manifest::parse(&content, format)?;
+        let plan = manifest.plan(workspace.cache(), self.prune)?;
+
+        if plan.is_empty() {
+            println!("Workspace already matches manifest");
+            return Ok(());
+        }
+
+        for change in &plan.tags {
+            print_change("tag", change, |t| (&t.name, t.location))?;
+        }
+
+        for change in &plan.repositories {
+            print_change("repository", change, |r| (&r.name, r.location))?;
+        }
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        if !self.yes
+            && !Confirm::new()
+                .with_prompt("Apply these changes to repo")
+                .default(false)
+                .interact()?
+        {
+            return Ok(());
+        }
+
+        workspace.apply_plan(plan)?;
+        Ok(())
+    }
+}
+
+fn print_change<T, F>(kind: &str, change: &Change<T>, describe: F) -> anyhow::Result<()>
+where
+    T: Serialize,
+    F: Fn(&T) -> (&String, repo_cli::Location),
+{
+    match change {
+        Change::Add(value) => {
+            let (name, location) = describe(value);
+            println!("+ {} '{}' ({})", kind, name, location);
+        }
+        Change::Remove(value) => {
+            let (name, location) = describe(value);
+            println!("- {} '{}' ({})", kind, name, location);
+        }
+        Change::Update { old, new } => {
+            let (name, old_location) = describe(old);
+            let (_, new_location) = describe(new);
+            println!("~ {} '{}' ({})", kind, name, new_location);
+
+            if old_location != new_location {
+                println!("    - location: {}", old_location);
+                println!("    + location: {}", new_location);
+            }
+
+            let old = toml::to_string_pretty(old)?;
+            let new = toml::to_string_pretty(new)?;
+            let old_lines: Vec<&str> = old.lines().collect();
+            let new_lines: Vec<&str> = new.lines().collect();
+
+            for line in old_lines.iter().filter(|l| !new_lines.contains(l)) {
+                println!("    - {}", line);
+            }
+
+            for line in new_lines.iter().filter(|l| !old_lines.contains(l)) {
+                println!("    + {}", line);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::io::Write;
+
+use anyhow::Context;
+use repo_cli::{util, Manifest, ManifestFormat, Workspace};
+
+use crate::cmd::{ManifestExportCmd, Run};
+
+impl Run for ManifestExportCmd {
+    fn run(self) -> anyhow::Result<()> {
+        let workspace = Workspace::new()?;
+
+        let format = match self.format.as_str() {
+            "json" => ManifestFormat::Json,
+            _ => ManifestFormat::Toml,
+        };
+
+        let ser = Manifest::from_cache(workspace.cache()).to_string(format)?;
+
+        match self.output {
+            Some(output) => {
+                let file = util::make_path_buf(output)?;
+                util::write_content(&file, |f| {
+                    f.write_fmt(format_args!("{}", ser)).map_err(Into::into)
+                })
+                .context(format!("failed to write file: {:#?}", file))
+            }
+            None => {
+                println!("{}", ser);
+                Ok(())
+            }
+        }
+    }
+}
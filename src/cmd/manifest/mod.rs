@@ -0,0 +1,13 @@
+use super::{ManifestCmd, Run};
+
+impl Run for ManifestCmd {
+    fn run(self) -> anyhow::Result<()> {
+        match self.cmd {
+            super::ManifestSubCmd::Apply(cmd) => cmd.run(),
+            super::ManifestSubCmd::Export(cmd) => cmd.run(),
+        }
+    }
+}
+
+mod apply;
+mod export;
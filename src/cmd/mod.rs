@@ -1,17 +1,24 @@
 mod add;
+mod cache;
 mod cli;
 mod config;
 mod edit;
 mod foreach;
+mod hooks;
+mod import;
 mod init;
 mod inspect;
 mod list;
+mod manifest;
+mod prune_branches;
 mod remove;
+mod status;
 mod tag;
 mod update;
 mod work;
 
 use anyhow::Result;
+use dialoguer::Confirm;
 
 pub use crate::cmd::cli::*;
 
@@ -23,16 +30,39 @@ impl Run for Cli {
     fn run(self) -> Result<()> {
         match self.command {
             Cmd::Add(cmd) => cmd.run(),
+            Cmd::Cache(cmd) => cmd.run(),
             Cmd::Config(cmd) => cmd.run(),
             Cmd::Edit(cmd) => cmd.run(),
             Cmd::Foreach(cmd) => cmd.run(),
+            Cmd::Hooks(cmd) => cmd.run(),
+            Cmd::Import(cmd) => cmd.run(),
             Cmd::Inspect(cmd) => cmd.run(),
             Cmd::Init(cmd) => cmd.run(),
             Cmd::List(cmd) => cmd.run(),
+            Cmd::Manifest(cmd) => cmd.run(),
+            Cmd::PruneBranches(cmd) => cmd.run(),
             Cmd::Remove(cmd) => cmd.run(),
+            Cmd::Status(cmd) => cmd.run(),
             Cmd::Tag(cmd) => cmd.run(),
             Cmd::Update(cmd) => cmd.run(),
             Cmd::Work(cmd) => cmd.run(),
         }
     }
 }
+
+/// Ask before acting on a name that the query only matched by prefix or fuzzy match
+///
+/// Exact matches of a name or an alias are accepted without asking.
+fn confirm_match(query: &str, name: &str, exact: bool) -> Result<bool> {
+    if exact {
+        return Ok(true);
+    }
+
+    Ok(Confirm::new()
+        .with_prompt(format!(
+            "'{}' matched '{}', do you want to continue",
+            query, name
+        ))
+        .default(false)
+        .interact()?)
+}
@@ -0,0 +1,86 @@
+use dialoguer::Confirm;
+use repo_cli::{Location, Workspace};
+
+use super::{PruneBranchesCmd, Run};
+
+impl Run for PruneBranchesCmd {
+    fn run(self) -> anyhow::Result<()> {
+        let workspace = Workspace::new()?;
+
+        let mut repositories = if self.names.is_empty() {
+            match (self.global, self.local, self.all) {
+                (true, false, false) => workspace
+                    .repositories()
+                    .into_iter()
+                    .filter(|r| r.location == Location::Global)
+                    .collect(),
+                (false, true, false) => workspace
+                    .repositories()
+                    .into_iter()
+                    .filter(|r| r.location == Location::Local)
+                    .collect(),
+                (false, false, true) => workspace.cache().repositories(),
+                _ => workspace.repositories(),
+            }
+        } else {
+            self.names
+                .iter()
+                .map(|name| workspace.resolve_repository(name).map(|(r, _)| r))
+                .collect::<repo_cli::Result<Vec<_>>>()?
+        };
+
+        if let Some(tags) = self.tags {
+            repositories.retain(|r| tags.iter().any(|t| r.tags.contains(t)));
+        }
+
+        repositories.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for repository in repositories {
+            let gone = match workspace.gone_branches(repository) {
+                Ok(Some(gone)) => gone,
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("{:>20.20} | error: {:#}", repository.name, e);
+                    continue;
+                }
+            };
+
+            if !gone.unmerged.is_empty() {
+                println!(
+                    "{:>20.20} | skipped unmerged: {}",
+                    repository.name,
+                    gone.unmerged.join(", ")
+                );
+            }
+
+            if gone.merged.is_empty() {
+                continue;
+            }
+
+            let branches = gone.merged.join(", ");
+            if self.dry_run {
+                println!("{:>20.20} | would delete: {}", repository.name, branches);
+                continue;
+            }
+
+            if !self.force
+                && !Confirm::new()
+                    .with_prompt(format!(
+                        "Are you sure you want to delete from '{}': {}",
+                        repository.name, branches
+                    ))
+                    .default(false)
+                    .interact()?
+            {
+                continue;
+            }
+
+            match workspace.delete_branches(repository, &gone.merged) {
+                Ok(()) => println!("{:>20.20} | deleted: {}", repository.name, branches),
+                Err(e) => println!("{:>20.20} | error: {:#}", repository.name, e),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -7,13 +7,28 @@ impl Run for RemoveCmd {
     fn run(self) -> anyhow::Result<()> {
         let mut workspace = Workspace::new()?;
 
-        for name in self.names {
-            if !self.force
+        for query in self.names {
+            // NOTE: Repositories hidden by the config filters can still be removed by their name
+            let (name, exact) = if workspace.has_repository(&query) {
+                (query.clone(), true)
+            } else {
+                let (repository, exact) = workspace.resolve_repository(&query)?;
+                (repository.name.clone(), exact)
+            };
+
+            // NOTE: A typo must not silently remove a different repository, even with --force
+            let prompt = if exact {
+                format!("Are you sure you want to remove: '{}' from repo", name)
+            } else {
+                format!(
+                    "'{}' matched '{}', are you sure you want to remove it from repo",
+                    query, name
+                )
+            };
+
+            if (!self.force || !exact)
                 && !Confirm::new()
-                    .with_prompt(&format!(
-                        "Are you sure you want to remove: '{}' from repo",
-                        name
-                    ))
+                    .with_prompt(prompt)
                     .default(false)
                     .interact()?
             {
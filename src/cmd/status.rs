@@ -0,0 +1,83 @@
+use repo_cli::{util, Location, Status, Workspace};
+
+use super::{Run, StatusCmd};
+
+impl Run for StatusCmd {
+    fn run(self) -> anyhow::Result<()> {
+        let workspace = Workspace::new()?;
+
+        let mut repositories = match (self.global, self.local, self.all) {
+            (true, false, false) => workspace
+                .repositories()
+                .into_iter()
+                .filter(|r| r.location == Location::Global)
+                .collect(),
+            (false, true, false) => workspace
+                .repositories()
+                .into_iter()
+                .filter(|r| r.location == Location::Local)
+                .collect(),
+            (false, false, true) => workspace.cache().repositories(),
+            _ => workspace.repositories(),
+        };
+
+        if let Some(tags) = self.tags {
+            repositories.retain(|r| tags.iter().any(|t| r.tags.contains(t)));
+        }
+
+        repositories.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let jobs = workspace.config().jobs(None);
+        let results = util::parallel_map(&repositories, jobs, |repository| {
+            workspace.status(repository)
+        });
+
+        for (repository, result) in repositories.iter().zip(results) {
+            let summary = match result {
+                Ok(Some(status)) => format_status(&status),
+                Ok(None) => "not cloned".to_owned(),
+                Err(e) => format!("error: {:#}", e),
+            };
+
+            println!("{:>20.20} | {}", repository.name, summary);
+        }
+
+        Ok(())
+    }
+}
+
+fn format_status(status: &Status) -> String {
+    let mut parts = Vec::new();
+
+    match (&status.branch, &status.upstream) {
+        (Some(branch), Some(upstream)) => {
+            parts.push(format!("{} -> {}", branch, upstream));
+            if status.ahead > 0 {
+                parts.push(format!("ahead {}", status.ahead));
+            }
+            if status.behind > 0 {
+                parts.push(format!("behind {}", status.behind));
+            }
+        }
+        (Some(branch), None) => parts.push(format!("{} (no upstream)", branch)),
+        (None, _) => parts.push("detached HEAD".to_owned()),
+    }
+
+    if let Some(operation) = &status.operation {
+        parts.push(format!("{} in progress", operation));
+    }
+
+    if status.changed > 0 {
+        parts.push(format!("{} changed", status.changed));
+    }
+
+    if status.untracked > 0 {
+        parts.push(format!("{} untracked", status.untracked));
+    }
+
+    if status.changed == 0 && status.untracked == 0 {
+        parts.push("clean".to_owned());
+    }
+
+    parts.join(", ")
+}
@@ -21,6 +21,46 @@ impl Run for TagAddCmd {
             builder = builder.path(path);
         }
 
+        if let Some(branch) = self.branch {
+            builder = builder.branch(branch);
+        }
+
+        if let Some(depth) = self.depth {
+            builder = builder.depth(depth);
+        }
+
+        if let Some(filter) = self.filter {
+            builder = builder.filter(filter);
+        }
+
+        if let Some(sparse) = self.sparse {
+            for path in sparse {
+                builder = builder.sparse(path);
+            }
+        }
+
+        if self.submodules {
+            builder = builder.submodules(self.submodules);
+        }
+
+        if self.lfs {
+            builder = builder.lfs(self.lfs);
+        }
+
+        if let Some(strategy) = self.update_strategy {
+            builder = builder.update_strategy(strategy.parse()?);
+        }
+
+        if self.autostash {
+            builder = builder.autostash(self.autostash);
+        }
+
+        if let Some(credentials) = self.credentials {
+            for provider in credentials {
+                builder = builder.credential(provider.parse()?);
+            }
+        }
+
         if let Some(clone) = self.clone {
             builder = builder.clone(clone);
         }
@@ -33,6 +73,7 @@ impl Run for TagAddCmd {
             builder = builder.priority(priority);
         }
 
-        workspace.add_tag(builder.build())
+        workspace.add_tag(builder.build())?;
+        Ok(())
     }
 }
@@ -3,20 +3,67 @@ use std::path::PathBuf;
 use anyhow::anyhow;
 use repo_cli::{Location, Workspace};
 
-use crate::cmd::{Run, TagEditCmd};
+use crate::cmd::{confirm_match, Run, TagEditCmd};
 
 impl Run for TagEditCmd {
     fn run(self) -> anyhow::Result<()> {
         let mut workspace = Workspace::new()?;
 
+        let (tag, exact) = workspace.resolve_tag(&self.name)?;
+        let name = tag.name.clone();
+        if !confirm_match(&self.name, &name, exact)? {
+            return Ok(());
+        }
+
         let mut tag = workspace
-            .take_tag(&self.name)
-            .ok_or_else(|| anyhow!("Tag: '{}' is not tracked by repo", &self.name))?;
+            .take_tag(&name)
+            .ok_or_else(|| anyhow!("Tag: '{}' is not tracked by repo", &name))?;
 
         if self.path.is_some() {
             tag.path = self.path.map(PathBuf::from);
         }
 
+        if self.branch.is_some() {
+            tag.branch = self.branch;
+        }
+
+        if self.depth.is_some() {
+            tag.depth = self.depth;
+        }
+
+        if self.filter.is_some() {
+            tag.filter = self.filter;
+        }
+
+        if self.sparse.is_some() {
+            tag.sparse = self.sparse;
+        }
+
+        if self.submodules {
+            tag.submodules = Some(self.submodules);
+        }
+
+        if self.lfs {
+            tag.lfs = Some(self.lfs);
+        }
+
+        if let Some(strategy) = self.update_strategy {
+            tag.update_strategy = Some(strategy.parse()?);
+        }
+
+        if self.autostash {
+            tag.autostash = Some(self.autostash);
+        }
+
+        if let Some(credentials) = self.credentials {
+            tag.credentials = Some(
+                credentials
+                    .iter()
+                    .map(|p| p.parse())
+                    .collect::<repo_cli::Result<_>>()?,
+            );
+        }
+
         if self.priority.is_some() {
             tag.priority = self.priority;
         }
@@ -7,13 +7,23 @@ impl Run for TagRemoveCmd {
     fn run(self) -> anyhow::Result<()> {
         let mut workspace = Workspace::new()?;
 
-        for name in self.names {
-            if !self.force
+        for query in self.names {
+            let (tag, exact) = workspace.resolve_tag(&query)?;
+            let name = tag.name.clone();
+
+            // NOTE: A typo must not silently remove a different tag, even with --force
+            let prompt = if exact {
+                format!("Are you sure you want to remove: '{}' from repo", name)
+            } else {
+                format!(
+                    "'{}' matched '{}', are you sure you want to remove it from repo",
+                    query, name
+                )
+            };
+
+            if (!self.force || !exact)
                 && !Confirm::new()
-                    .with_prompt(&format!(
-                        "Are you sure you want to remove: '{}' from repo",
-                        name
-                    ))
+                    .with_prompt(prompt)
                     .default(false)
                     .interact()?
             {
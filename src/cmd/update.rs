@@ -1,4 +1,5 @@
-use repo_cli::{Location, Repository, Workspace};
+use anyhow::bail;
+use repo_cli::{progress, util, Location, Repository, UpdateOptions, UpdateStatus, Workspace};
 
 use super::{Run, UpdateCmd};
 
@@ -28,8 +29,76 @@ impl Run for UpdateCmd {
                 .collect::<Vec<&Repository>>();
         }
 
-        for repository in repositories {
-            workspace.update_remotes(repository)?;
+        let options = UpdateOptions {
+            unshallow: self.unshallow,
+            strategy: self.strategy.map(|s| s.parse()).transpose()?,
+            autostash: self.autostash,
+            all_branches: self.all_branches,
+            prune_remotes: self.prune_remotes,
+        };
+
+        let jobs = self.jobs.unwrap_or_else(|| workspace.config().jobs(None));
+        progress::enable(true);
+        let results = util::parallel_map(&repositories, jobs, |repository| {
+            workspace.update_remotes(repository, &options)
+        });
+        progress::finish();
+
+        let mut succeeded = 0;
+        let mut skipped = Vec::new();
+        let mut failed = Vec::new();
+        let mut gone = Vec::new();
+        let mut remotes = Vec::new();
+        for (repository, result) in repositories.iter().zip(results) {
+            match result {
+                Ok(UpdateStatus::Cloned) => succeeded += 1,
+                Ok(UpdateStatus::Updated {
+                    gone: branches,
+                    remotes: changes,
+                }) => {
+                    succeeded += 1;
+                    if !branches.is_empty() {
+                        gone.push((&repository.name, branches));
+                    }
+                    if !changes.is_empty() {
+                        remotes.push((&repository.name, changes));
+                    }
+                }
+                Ok(UpdateStatus::Skipped(reason)) => skipped.push((&repository.name, reason)),
+                Err(e) => failed.push((&repository.name, e)),
+            }
+        }
+
+        println!(
+            "{} succeeded, {} failed, {} skipped",
+            succeeded,
+            failed.len(),
+            skipped.len()
+        );
+
+        for (name, reason) in &skipped {
+            println!("{:>20.20} | skipped: {}", name, reason);
+        }
+
+        for (name, error) in &failed {
+            println!("{:>20.20} | failed: {:#}", name, error);
+        }
+
+        for (name, changes) in &remotes {
+            let changes: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+            println!("{:>20.20} | remotes: {}", name, changes.join(", "));
+        }
+
+        for (name, branches) in &gone {
+            println!("{:>20.20} | upstream gone: {}", name, branches.join(", "));
+        }
+
+        if !gone.is_empty() {
+            println!("Use 'repo prune-branches' to delete branches whose upstream is gone");
+        }
+
+        if !failed.is_empty() {
+            bail!("{} repositories failed to update", failed.len());
         }
 
         Ok(())
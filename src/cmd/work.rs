@@ -6,9 +6,20 @@ use super::{Run, WorkCmd};
 impl Run for WorkCmd {
     fn run(self) -> anyhow::Result<()> {
         let workspace = Workspace::new()?;
-        let repo = workspace
-            .get_repository(&self.name)
-            .ok_or_else(|| anyhow!("Repository: '{}' is not tracked by repo", &self.name))?;
+        let (repo, exact) = workspace.resolve_repository(&self.name)?;
+
+        // NOTE: The output is evaluated by the shell, the resolved name goes to stderr
+        if !exact {
+            eprintln!("'{}' matched '{}'", self.name, repo.name);
+        }
+
+        if self.list {
+            for hook in repo.work_hooks(workspace.cache()) {
+                println!("{}", hook);
+            }
+
+            return Ok(());
+        }
 
         let path = workspace
             .config()
@@ -16,16 +27,14 @@ impl Run for WorkCmd {
             .join(repo.resolve_workspace_path(workspace.cache()));
 
         if !path.is_dir() {
-            return Err(anyhow!("Could not find repository: '{}' in workspace path: '{}'. Repository needs to be cloned.", self.name, path.display()));
+            return Err(anyhow!("Could not find repository: '{}' in workspace path: '{}'. Repository needs to be cloned.", repo.name, path.display()));
         }
 
         let mut commands = Vec::new();
         commands.push(format!("cd {}", path.display()));
 
         if !self.quick {
-            if let Some(work) = &repo.work {
-                commands.push(work.clone());
-            }
+            commands.extend(repo.work_hooks(workspace.cache()));
         }
 
         println!("{}", commands.join(" && "));
@@ -1,13 +1,14 @@
 use crate::{
     config::{ConfigData, RawConfigData},
-    query::Scheme,
+    git::{Backend, CredentialProvider, UpdateStrategy},
+    query::{AliasUrl, Scheme},
     util,
 };
-use anyhow::{Context, Result};
+use crate::{error::Context, Error, Result};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashSet},
     convert::{TryFrom, TryInto},
     path::{Path, PathBuf},
 };
@@ -30,12 +31,31 @@ impl Default for ConfigData {
             root: Some((*DEFAULT_ROOT).to_path_buf()),
             root_str: Some(format!("{}", (*DEFAULT_ROOT).display())),
             cli: Some(false),
+            backend: None,
+            jobs: Some(
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1),
+            ),
+            connect_timeout: Some(30),
+            timeout: Some(600),
+            retries: Some(2),
+            mirror: None,
+            mirror_str: None,
+            update_strategy: Some(UpdateStrategy::default()),
+            autostash: Some(false),
+            credentials: Some(vec![
+                CredentialProvider::Token,
+                CredentialProvider::SshKey,
+                CredentialProvider::Default,
+            ]),
             host: Some("github.com".to_owned()),
             ssh_user: Some("git".to_owned()),
             scheme: Some(Scheme::Https),
             shell: Some(shell),
             include: HashSet::new(),
             exclude: HashSet::new(),
+            hosts: BTreeMap::new(),
             path: None,
         }
     }
@@ -47,29 +67,53 @@ impl ConfigData {
             root: None,
             root_str: None,
             cli: None,
+            backend: None,
+            jobs: None,
+            connect_timeout: None,
+            timeout: None,
+            retries: None,
+            mirror: None,
+            mirror_str: None,
+            update_strategy: None,
+            autostash: None,
+            credentials: None,
             host: None,
             ssh_user: None,
             scheme: None,
             shell: None,
             include: HashSet::new(),
             exclude: HashSet::new(),
+            hosts: BTreeMap::new(),
             path: None,
         }
     }
     fn from_raw(raw: RawConfigData) -> Self {
         let root_str = raw.root.clone();
         let root = raw.root.and_then(|path| util::make_path_buf(path).ok());
+        let mirror_str = raw.mirror.clone();
+        let mirror = raw.mirror.and_then(|path| util::make_path_buf(path).ok());
 
         Self {
             root,
             root_str,
             cli: raw.cli,
+            backend: raw.backend,
+            jobs: raw.jobs,
+            connect_timeout: raw.connect_timeout,
+            timeout: raw.timeout,
+            retries: raw.retries,
+            mirror,
+            mirror_str,
+            update_strategy: raw.update_strategy,
+            autostash: raw.autostash,
+            credentials: raw.credentials,
             host: raw.default_host,
             ssh_user: raw.default_ssh_user,
             scheme: raw.default_scheme,
             shell: raw.shell,
             include: raw.include.unwrap_or_default(),
             exclude: raw.exclude.unwrap_or_default(),
+            hosts: raw.hosts.unwrap_or_default(),
             path: Some(raw.path),
         }
     }
@@ -95,15 +139,31 @@ impl ConfigData {
 
         let shell = self.shell.as_ref().map(|shell| shell.to_vec());
 
+        let hosts = if self.hosts.is_empty() {
+            None
+        } else {
+            Some(self.hosts.clone())
+        };
+
         RawConfigData {
             root: self.root_str.clone(),
             cli: self.cli,
+            backend: self.backend,
+            jobs: self.jobs,
+            connect_timeout: self.connect_timeout,
+            timeout: self.timeout,
+            retries: self.retries,
+            mirror: self.mirror_str.clone(),
+            update_strategy: self.update_strategy,
+            autostash: self.autostash,
+            credentials: self.credentials.clone(),
             default_host: self.host.clone(),
             default_ssh_user: self.ssh_user.clone(),
             default_scheme: self.scheme,
             shell,
             include,
             exclude,
+            hosts,
             path: self.path.clone().unwrap(),
         }
     }
@@ -118,6 +178,22 @@ impl ConfigData {
             content
         ))?;
         raw.path = PathBuf::from(path.as_ref().parent().unwrap());
+
+        let mut hosts = raw.hosts.iter().flat_map(|hosts| hosts.keys());
+        if let Some(name) = hosts.clone().find(|name| AliasUrl::is_reserved_alias(name)) {
+            return Err(Error::InvalidValue(format!(
+                "host alias '{}' in {:#?} is a url scheme, queries starting with '{}:' are parsed as urls",
+                name, path, name
+            )));
+        }
+
+        if let Some(name) = hosts.find(|name| !AliasUrl::is_valid_alias(name)) {
+            return Err(Error::InvalidValue(format!(
+                "host alias '{}' in {:#?} must start with a letter and only contain letters, digits, '+' or '-'",
+                name, path
+            )));
+        }
+
         Ok(raw.into())
     }
 }
@@ -1,9 +1,10 @@
 use crate::{
-    config::{Config, ConfigData},
+    config::{Config, ConfigData, HostAlias, RawConfigData},
+    git::{Backend, CredentialProvider, UpdateStrategy},
     query::Scheme,
     util, Location,
 };
-use anyhow::{anyhow, Context, Result};
+use crate::{error::Context, Error, Result};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -44,6 +45,35 @@ lazy_static! {
     };
 }
 
+/// Configuration as if no config file exists, only the default values apply
+impl Default for Config {
+    fn default() -> Self {
+        let mut global = ConfigData::new();
+        global.path = Some(Config::global_path().to_path_buf());
+        let mut local = ConfigData::new();
+        local.path = Some(Config::local_path().to_path_buf());
+
+        Self {
+            global,
+            local,
+            default: ConfigData::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Config {
+    /// Configuration with a global config file of the given content
+    pub(crate) fn from_toml(content: &str) -> Self {
+        let mut raw: RawConfigData = toml::from_str(content).unwrap();
+        raw.path = Config::global_path().to_path_buf();
+        Self {
+            global: raw.into(),
+            ..Default::default()
+        }
+    }
+}
+
 impl Config {
     pub fn new() -> Result<Self> {
         let global_path: &Path = &GLOBAL_CONFIG_PATH;
@@ -128,6 +158,17 @@ impl Config {
         }
     }
 
+    /// Directory of the bare mirrors that clones borrow objects from, `None` if disabled
+    pub fn mirror(&self, location: Option<Location>) -> Option<&Path> {
+        let result = match location {
+            Some(Location::Global) => self.global.mirror.as_ref(),
+            Some(Location::Local) => self.local.mirror.as_ref(),
+            None => self.local.mirror.as_ref().or(self.global.mirror.as_ref()),
+        };
+
+        result.map(|p| p.as_path())
+    }
+
     pub fn cli(&self, location: Option<Location>) -> bool {
         if let Some(l) = location {
             if let Some(result) = match l {
@@ -147,6 +188,158 @@ impl Config {
         }
     }
 
+    /// Backend used for git operations, decided by the `cli` flag if no backend is set
+    pub fn backend(&self, location: Option<Location>) -> Backend {
+        let result = match location {
+            Some(Location::Global) => self.global.backend,
+            Some(Location::Local) => self.local.backend,
+            None => self.local.backend.or(self.global.backend),
+        };
+
+        result.unwrap_or_else(|| Backend::from_cli(self.cli(location)))
+    }
+
+    /// Backend set in the config, without falling back to the `cli` option
+    pub fn backend_setting(&self) -> Option<Backend> {
+        self.local.backend.or(self.global.backend)
+    }
+
+    pub fn update_strategy(&self, location: Option<Location>) -> UpdateStrategy {
+        if let Some(l) = location {
+            if let Some(result) = match l {
+                Location::Global => self.global.update_strategy,
+                Location::Local => self.local.update_strategy,
+            } {
+                return result;
+            }
+        }
+
+        if let Some(local) = self.local.update_strategy {
+            local
+        } else if let Some(global) = self.global.update_strategy {
+            global
+        } else {
+            self.default.update_strategy.unwrap()
+        }
+    }
+
+    pub fn autostash(&self, location: Option<Location>) -> bool {
+        if let Some(l) = location {
+            if let Some(result) = match l {
+                Location::Global => self.global.autostash,
+                Location::Local => self.local.autostash,
+            } {
+                return result;
+            }
+        }
+
+        if let Some(local) = self.local.autostash {
+            local
+        } else if let Some(global) = self.global.autostash {
+            global
+        } else {
+            self.default.autostash.unwrap()
+        }
+    }
+
+    pub fn credentials(&self, location: Option<Location>) -> &[CredentialProvider] {
+        if let Some(l) = location {
+            if let Some(result) = match l {
+                Location::Global => &self.global.credentials,
+                Location::Local => &self.local.credentials,
+            } {
+                return result;
+            }
+        }
+
+        if let Some(local) = &self.local.credentials {
+            local
+        } else if let Some(global) = &self.global.credentials {
+            global
+        } else {
+            self.default.credentials.as_ref().unwrap()
+        }
+    }
+
+    pub fn jobs(&self, location: Option<Location>) -> usize {
+        if let Some(l) = location {
+            if let Some(result) = match l {
+                Location::Global => self.global.jobs,
+                Location::Local => self.local.jobs,
+            } {
+                return result;
+            }
+        }
+
+        if let Some(local) = self.local.jobs {
+            local
+        } else if let Some(global) = self.global.jobs {
+            global
+        } else {
+            self.default.jobs.unwrap()
+        }
+    }
+
+    /// Seconds to wait for a remote to connect or send data, `0` waits forever
+    pub fn connect_timeout(&self, location: Option<Location>) -> u64 {
+        if let Some(l) = location {
+            if let Some(result) = match l {
+                Location::Global => self.global.connect_timeout,
+                Location::Local => self.local.connect_timeout,
+            } {
+                return result;
+            }
+        }
+
+        if let Some(local) = self.local.connect_timeout {
+            local
+        } else if let Some(global) = self.global.connect_timeout {
+            global
+        } else {
+            self.default.connect_timeout.unwrap()
+        }
+    }
+
+    /// Seconds a single attempt of a network operation may take, `0` waits forever
+    pub fn timeout(&self, location: Option<Location>) -> u64 {
+        if let Some(l) = location {
+            if let Some(result) = match l {
+                Location::Global => self.global.timeout,
+                Location::Local => self.local.timeout,
+            } {
+                return result;
+            }
+        }
+
+        if let Some(local) = self.local.timeout {
+            local
+        } else if let Some(global) = self.global.timeout {
+            global
+        } else {
+            self.default.timeout.unwrap()
+        }
+    }
+
+    /// Number of times a network operation is retried after a network error
+    pub fn retries(&self, location: Option<Location>) -> u32 {
+        if let Some(l) = location {
+            if let Some(result) = match l {
+                Location::Global => self.global.retries,
+                Location::Local => self.local.retries,
+            } {
+                return result;
+            }
+        }
+
+        if let Some(local) = self.local.retries {
+            local
+        } else if let Some(global) = self.global.retries {
+            global
+        } else {
+            self.default.retries.unwrap()
+        }
+    }
+
     pub fn host(&self, location: Option<Location>) -> &str {
         if let Some(l) = location {
             let result = match l {
@@ -295,6 +488,50 @@ impl Config {
         result
     }
 
+    /// Find a host alias by name. Aliases in the local config override the global ones
+    pub fn host_alias(&self, name: &str) -> Option<&HostAlias> {
+        self.local
+            .hosts
+            .get(name)
+            .or_else(|| self.global.hosts.get(name))
+    }
+
+    /// Find the host alias that points to a host. Aliases in the local config are checked first
+    pub fn host_alias_by_host(&self, host: &str) -> Option<&HostAlias> {
+        self.local
+            .hosts
+            .values()
+            .chain(self.global.hosts.values())
+            .find(|alias| alias.host.eq_ignore_ascii_case(host))
+    }
+
+    pub fn host_aliases(&self, location: Option<Location>) -> Vec<(&str, &HostAlias)> {
+        if let Some(l) = location {
+            let hosts = match l {
+                Location::Global => &self.global.hosts,
+                Location::Local => &self.local.hosts,
+            };
+
+            return hosts.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        }
+
+        let mut result: Vec<(&str, &HostAlias)> = self
+            .local
+            .hosts
+            .iter()
+            .map(|(k, v)| (k.as_str(), v))
+            .collect();
+
+        for (name, alias) in self.global.hosts.iter() {
+            if !self.local.hosts.contains_key(name) {
+                result.push((name.as_str(), alias));
+            }
+        }
+
+        result.sort_by_key(|(name, _)| *name);
+        result
+    }
+
     // --------------------------------------------------------------------------------------------
     // Set functions for config command
 
@@ -311,6 +548,16 @@ impl Config {
         self.global.root_str = Some(raw.to_owned());
     }
 
+    pub fn set_mirror(&mut self, value: Option<(&str, PathBuf)>, location: Option<Location>) {
+        let data = match location {
+            Some(Location::Local) => &mut self.local,
+            _ => &mut self.global,
+        };
+
+        data.mirror_str = value.as_ref().map(|(raw, _)| (*raw).to_owned());
+        data.mirror = value.map(|(_, path)| path);
+    }
+
     pub fn set_cli(&mut self, value: bool, location: Option<Location>) {
         if let Some(l) = location {
             if l == Location::Local {
@@ -322,6 +569,94 @@ impl Config {
         self.global.cli = Some(value);
     }
 
+    pub fn set_backend(&mut self, value: Backend, location: Option<Location>) {
+        if let Some(l) = location {
+            if l == Location::Local {
+                self.local.backend = Some(value);
+                return;
+            }
+        }
+
+        self.global.backend = Some(value);
+    }
+
+    pub fn set_update_strategy(&mut self, value: UpdateStrategy, location: Option<Location>) {
+        if let Some(l) = location {
+            if l == Location::Local {
+                self.local.update_strategy = Some(value);
+                return;
+            }
+        }
+
+        self.global.update_strategy = Some(value);
+    }
+
+    pub fn set_autostash(&mut self, value: bool, location: Option<Location>) {
+        if let Some(l) = location {
+            if l == Location::Local {
+                self.local.autostash = Some(value);
+                return;
+            }
+        }
+
+        self.global.autostash = Some(value);
+    }
+
+    pub fn set_credentials(&mut self, value: Vec<CredentialProvider>, location: Option<Location>) {
+        if let Some(l) = location {
+            if l == Location::Local {
+                self.local.credentials = Some(value);
+                return;
+            }
+        }
+
+        self.global.credentials = Some(value);
+    }
+
+    pub fn set_jobs(&mut self, value: usize, location: Option<Location>) {
+        if let Some(l) = location {
+            if l == Location::Local {
+                self.local.jobs = Some(value);
+                return;
+            }
+        }
+
+        self.global.jobs = Some(value);
+    }
+
+    pub fn set_connect_timeout(&mut self, value: u64, location: Option<Location>) {
+        if let Some(l) = location {
+            if l == Location::Local {
+                self.local.connect_timeout = Some(value);
+                return;
+            }
+        }
+
+        self.global.connect_timeout = Some(value);
+    }
+
+    pub fn set_timeout(&mut self, value: u64, location: Option<Location>) {
+        if let Some(l) = location {
+            if l == Location::Local {
+                self.local.timeout = Some(value);
+                return;
+            }
+        }
+
+        self.global.timeout = Some(value);
+    }
+
+    pub fn set_retries(&mut self, value: u32, location: Option<Location>) {
+        if let Some(l) = location {
+            if l == Location::Local {
+                self.local.retries = Some(value);
+                return;
+            }
+        }
+
+        self.global.retries = Some(value);
+    }
+
     pub fn set_host(&mut self, host: &str, location: Option<Location>) {
         if let Some(l) = location {
             if l == Location::Local {
@@ -427,7 +762,6 @@ impl Config {
         util::write_content(&file, |f| {
             f.write_fmt(format_args!("{}", ser))
                 .context(format!("failed to write file: {:#?}", file))
-                .map_err(Into::into)
         })
     }
 }
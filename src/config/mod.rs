@@ -1,6 +1,12 @@
-use crate::query::Scheme;
+use crate::{
+    git::{Backend, CredentialProvider, UpdateStrategy},
+    query::Scheme,
+};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+};
 
 #[derive(Debug)]
 pub struct Config {
@@ -14,12 +20,23 @@ pub struct ConfigData {
     root: Option<PathBuf>,
     root_str: Option<String>,
     cli: Option<bool>,
+    backend: Option<Backend>,
+    jobs: Option<usize>,
+    connect_timeout: Option<u64>,
+    timeout: Option<u64>,
+    retries: Option<u32>,
+    mirror: Option<PathBuf>,
+    mirror_str: Option<String>,
+    update_strategy: Option<UpdateStrategy>,
+    autostash: Option<bool>,
+    credentials: Option<Vec<CredentialProvider>>,
     host: Option<String>,
     ssh_user: Option<String>,
     scheme: Option<Scheme>,
     shell: Option<Vec<String>>,
     include: HashSet<String>,
     exclude: HashSet<String>,
+    hosts: BTreeMap<String, HostAlias>,
     path: Option<PathBuf>,
 }
 
@@ -27,16 +44,60 @@ pub struct ConfigData {
 struct RawConfigData {
     root: Option<String>,
     cli: Option<bool>,
+    backend: Option<Backend>,
+    jobs: Option<usize>,
+    connect_timeout: Option<u64>,
+    timeout: Option<u64>,
+    retries: Option<u32>,
+    mirror: Option<String>,
+    update_strategy: Option<UpdateStrategy>,
+    autostash: Option<bool>,
+    credentials: Option<Vec<CredentialProvider>>,
     default_host: Option<String>,
     default_ssh_user: Option<String>,
     default_scheme: Option<Scheme>,
     shell: Option<Vec<String>>,
     include: Option<HashSet<String>>,
     exclude: Option<HashSet<String>>,
+    hosts: Option<BTreeMap<String, HostAlias>>,
 
     #[serde(skip)]
     path: PathBuf,
 }
 
+/// A named shorthand for a git host
+///
+/// With an alias named `gl` a query like `gl:group/project` expands to a url on the alias' host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostAlias {
+    /// Host name, optionally with a port
+    pub host: String,
+    /// Scheme of the generated url. Defaults to `default_scheme`
+    pub scheme: Option<Scheme>,
+    /// User used with the ssh scheme. Defaults to `default_ssh_user`
+    pub ssh_user: Option<String>,
+    /// Template of the url path. `{path}` is replaced with the path of the query, `{name}` with
+    /// its last segment and `{group}` with the segments before it
+    pub template: Option<String>,
+    /// How the default repository name is derived from the path. Defaults to `last`
+    pub name: Option<NameRule>,
+    /// Use the group of the path as the default workspace path of the repository
+    pub group_path: Option<bool>,
+    /// Private ssh key used by the `ssh-key` credential provider for this host
+    pub ssh_key: Option<String>,
+    /// Environment variable holding the token used by the `token` credential provider for this host
+    pub token_env: Option<String>,
+}
+
+/// Rule used to derive a repository name from a path like `org/group/project`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameRule {
+    /// The last segment of the path: `project`
+    Last,
+    /// Every segment of the path joined by a dash: `org-group-project`
+    Full,
+}
+
 mod data;
 mod inner;
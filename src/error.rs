@@ -0,0 +1,369 @@
+use std::{fmt, path::PathBuf, time::Duration};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned by the library
+///
+/// The variants are stable so that tools embedding the library can react to a failure without
+/// matching its message. Causes added with [`Context`] and the errors of the wrapped libraries are
+/// the source of the error. The alternate format `{:#}` includes every cause.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// No tracked repository matches the name, with the closest names
+    RepositoryNotFound {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    /// More than one tracked repository matches the name
+    RepositoryAmbiguous {
+        name: String,
+        matches: Vec<String>,
+    },
+    /// A repository with the name is already tracked
+    RepositoryExists(String),
+    /// No tag matches the name, with the closest names
+    TagNotFound {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    /// More than one tag matches the name
+    TagAmbiguous {
+        name: String,
+        matches: Vec<String>,
+    },
+    /// A tag with the name already exists
+    TagExists(String),
+    /// The repository has not been cloned into the workspace
+    NotCloned {
+        name: String,
+        path: PathBuf,
+    },
+    /// A clone is not inside of the workspace root
+    OutsideRoot {
+        path: PathBuf,
+        root: PathBuf,
+    },
+    /// The checked out branch has diverged from its upstream
+    NonFastForward,
+    /// HEAD does not point to a branch
+    DetachedHead,
+    /// The HEAD of the remote does not point to a branch
+    NoDefaultBranch(String),
+    /// Rebasing the checked out branch onto its upstream stopped on conflicts and was aborted
+    RebaseConflict,
+    /// A local branch has commits that are not merged into the checked out branch
+    NotMerged(String),
+    /// A hook command exited with an error
+    HookFailed {
+        command: String,
+    },
+    /// Submodules that could not be updated, with the cause of each
+    SubmodulesFailed(Vec<String>),
+    /// A query cannot be turned into a url
+    InvalidQuery(String),
+    /// A value is not valid for an option
+    InvalidValue(String),
+    /// A network operation did not finish within its timeout
+    Timeout(Duration),
+    /// A git command exited with an error, with the cause from its error output
+    Command {
+        command: String,
+        code: Option<i32>,
+        reason: Option<String>,
+    },
+    /// libgit2 failed
+    Git(git2::Error),
+    /// Reading or writing a file or running a command failed
+    Io(std::io::Error),
+    /// A url cannot be parsed
+    Url(url::ParseError),
+    /// A toml file cannot be parsed
+    Deserialize(toml::de::Error),
+    /// A value cannot be written as toml
+    Serialize(toml::ser::Error),
+    /// A json file cannot be parsed or written
+    Json(serde_json::Error),
+    /// An error with a message describing what failed
+    Context {
+        message: String,
+        source: Box<Error>,
+    },
+    Other(String),
+}
+
+impl Error {
+    /// Create an error that does not have a variant of its own
+    pub(crate) fn other<S: Into<String>>(message: S) -> Self {
+        Error::Other(message.into())
+    }
+
+    /// Describe what failed with this error as the cause
+    pub(crate) fn context<S: Into<String>>(self, message: S) -> Self {
+        Error::Context {
+            message: message.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The error and the errors it was caused by, starting with the error itself
+    pub fn chain(&self) -> impl Iterator<Item = &Error> {
+        std::iter::successors(Some(self), |error| match error {
+            Error::Context { source, .. } => Some(source),
+            _ => None,
+        })
+    }
+
+    /// The error without the context added to it
+    pub fn root_cause(&self) -> &Error {
+        self.chain().last().unwrap_or(self)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::RepositoryNotFound { name, suggestions } if suggestions.is_empty() => {
+                write!(f, "Repository: '{}' is not tracked by repo", name)
+            }
+            Error::RepositoryNotFound { name, suggestions } => write!(
+                f,
+                "Repository: '{}' is not tracked by repo. Did you mean: {}?",
+                name,
+                suggestions.join(", ")
+            ),
+            Error::RepositoryAmbiguous { name, matches } => write!(
+                f,
+                "Repository: '{}' is ambiguous, it matches: {}",
+                name,
+                matches.join(", ")
+            ),
+            Error::RepositoryExists(name) => {
+                write!(f, "Repository: {} already exist in repo", name)
+            }
+            Error::TagNotFound { name, suggestions } if suggestions.is_empty() => {
+                write!(f, "Tag: '{}' is not in repo", name)
+            }
+            Error::TagNotFound { name, suggestions } => write!(
+                f,
+                "Tag: '{}' is not in repo. Did you mean: {}?",
+                name,
+                suggestions.join(", ")
+            ),
+            Error::TagAmbiguous { name, matches } => write!(
+                f,
+                "Tag: '{}' is ambiguous, it matches: {}",
+                name,
+                matches.join(", ")
+            ),
+            Error::TagExists(name) => write!(f, "Tag: {} already exist in repo", name),
+            Error::NotCloned { name, path } => write!(
+                f,
+                "Repository: '{}' has not been cloned to: '{}'",
+                name,
+                path.display()
+            ),
+            Error::OutsideRoot { path, root } => write!(
+                f,
+                "'{}' is not inside of the workspace root: '{}'",
+                path.display(),
+                root.display()
+            ),
+            Error::NonFastForward => write!(f, "local branch cannot be fast-forward merged"),
+            Error::DetachedHead => write!(f, "HEAD does not point to a branch"),
+            Error::NoDefaultBranch(remote) => {
+                write!(f, "remote '{}' does not have a default branch", remote)
+            }
+            Error::RebaseConflict => write!(f, "failed to rebase local branch, conflicts found"),
+            Error::NotMerged(branch) => write!(f, "branch '{}' is not fully merged", branch),
+            Error::HookFailed { command } => write!(f, "External command failed: {}", command),
+            Error::SubmodulesFailed(failed) => write!(
+                f,
+                "failed to update {} submodules:\n  {}",
+                failed.len(),
+                failed.join("\n  ")
+            ),
+            Error::InvalidQuery(message) | Error::InvalidValue(message) => {
+                write!(f, "{}", message)
+            }
+            Error::Timeout(timeout) => {
+                write!(f, "operation timed out after {}s", timeout.as_secs_f32())
+            }
+            Error::Command {
+                command,
+                code,
+                reason: Some(reason),
+            } => write!(
+                f,
+                "command '{}' exited with return code: {:?}: {}",
+                command, code, reason
+            ),
+            Error::Command { command, code, .. } => {
+                write!(
+                    f,
+                    "command '{}' exited with return code: {:?}",
+                    command, code
+                )
+            }
+            Error::Git(_) => write!(f, "libgit2 failed"),
+            Error::Io(_) => write!(f, "io error"),
+            Error::Url(_) => write!(f, "invalid url"),
+            Error::Deserialize(_) => write!(f, "failed to parse toml"),
+            Error::Serialize(_) => write!(f, "failed to serialize toml"),
+            Error::Json(_) => write!(f, "failed to parse or write json"),
+            Error::Context { message, .. } => write!(f, "{}", message),
+            Error::Other(message) => write!(f, "{}", message),
+        }?;
+
+        match std::error::Error::source(self) {
+            Some(source) if f.alternate() => write!(f, ": {:#}", source),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Context { source, .. } => Some(source.as_ref()),
+            Error::Git(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Url(e) => Some(e),
+            Error::Deserialize(e) => Some(e),
+            Error::Serialize(e) => Some(e),
+            Error::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<git2::Error> for Error {
+    fn from(error: git2::Error) -> Self {
+        Error::Git(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(error: url::ParseError) -> Self {
+        Error::Url(error)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(error: toml::de::Error) -> Self {
+        Error::Deserialize(error)
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(error: toml::ser::Error) -> Self {
+        Error::Serialize(error)
+    }
+}
+
+impl From<glob::GlobError> for Error {
+    fn from(error: glob::GlobError) -> Self {
+        Error::Io(error.into_error())
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(error: regex::Error) -> Self {
+        Error::Other(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Json(error)
+    }
+}
+
+/// Describe what failed when an error is returned
+pub(crate) trait Context<T> {
+    fn context<S: Into<String>>(self, message: S) -> Result<T>;
+}
+
+impl<T, E> Context<T> for std::result::Result<T, E>
+where
+    E: Into<Error>,
+{
+    fn context<S: Into<String>>(self, message: S) -> Result<T> {
+        self.map_err(|error| error.into().context(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_chain() {
+        let result: Result<()> = Err(Error::NonFastForward);
+        let error = result
+            .context("failed to merge")
+            .context("update failed")
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "update failed");
+        assert!(matches!(error.root_cause(), Error::NonFastForward));
+        assert_eq!(error.chain().count(), 3);
+
+        let error = anyhow::Error::new(error);
+        assert_eq!(
+            format!("{:#}", error),
+            "update failed: failed to merge: local branch cannot be fast-forward merged"
+        );
+    }
+
+    #[test]
+    fn wrapped_errors() {
+        use std::error::Error as _;
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "config.toml is missing");
+        let error = Error::from(io).context("failed to read config");
+
+        assert_eq!(error.to_string(), "failed to read config");
+        assert_eq!(
+            format!("{:#}", error),
+            "failed to read config: io error: config.toml is missing"
+        );
+
+        let source = error.root_cause().source().unwrap();
+        let io = source.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+
+        let error = anyhow::Error::new(error);
+        assert_eq!(
+            format!("{:#}", error),
+            "failed to read config: io error: config.toml is missing"
+        );
+    }
+
+    #[test]
+    fn not_found_suggestions() {
+        let error = Error::RepositoryNotFound {
+            name: "rpo".to_owned(),
+            suggestions: Vec::new(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Repository: 'rpo' is not tracked by repo"
+        );
+
+        let error = Error::TagNotFound {
+            name: "wrk".to_owned(),
+            suggestions: vec!["work".to_owned(), "web".to_owned()],
+        };
+        assert_eq!(
+            error.to_string(),
+            "Tag: 'wrk' is not in repo. Did you mean: work, web?"
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Implementation used to execute git operations
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    /// The libgit2 library linked into repo
+    #[default]
+    Libgit,
+    /// The git command line
+    Cli,
+    /// libgit2, retrying with the git command line when libgit2 cannot authenticate or does not
+    /// support the remote's protocol
+    Auto,
+}
+
+impl Backend {
+    /// Backend matching the `cli` flag of a repository or the config
+    pub fn from_cli(use_cli: bool) -> Self {
+        if use_cli {
+            Backend::Cli
+        } else {
+            Backend::Libgit
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = match self {
+            Backend::Libgit => "libgit",
+            Backend::Cli => "cli",
+            Backend::Auto => "auto",
+        };
+
+        write!(f, "{}", result)
+    }
+}
+
+impl FromStr for Backend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "libgit" => Ok(Backend::Libgit),
+            "cli" => Ok(Backend::Cli),
+            "auto" => Ok(Backend::Auto),
+            _ => Err(Error::InvalidValue(format!(
+                "'{}' is not a backend: [libgit, cli, auto]",
+                s
+            ))),
+        }
+    }
+}
@@ -1,6 +1,15 @@
-use crate::{util::process, Remote};
-use anyhow::{anyhow, Result};
-use std::path::Path;
+use crate::{error::Context, Error, Result};
+use crate::{
+    git::{network, Status},
+    util::process,
+    Remote,
+};
+use std::{
+    io::Read,
+    path::Path,
+    process::{Command, Output, Stdio},
+    time::Duration,
+};
 
 pub fn init<P>(path: P, remotes: &[Remote]) -> Result<()>
 where
@@ -20,10 +29,11 @@ where
         .map_err(Into::into)
         .and_then(|st| match st.code() {
             Some(0) => Ok(()),
-            st => Err(anyhow!(
-                "command 'git init' exited with return code: {:#?}",
-                st
-            )),
+            st => Err(Error::Command {
+                command: "git init".to_owned(),
+                code: st,
+                reason: None,
+            }),
         })?;
 
     for remote in remotes {
@@ -39,11 +49,11 @@ where
             .map_err(Into::into)
             .and_then(|st| match st.code() {
                 Some(0) => Ok(()),
-                st => Err(anyhow!(
-                    "command '{}' exited with return code: {:#?}",
-                    command,
-                    st
-                )),
+                st => Err(Error::Command {
+                    command: command.to_owned(),
+                    code: st,
+                    reason: None,
+                }),
             })?;
     }
 
@@ -51,42 +61,382 @@ where
 }
 
 pub fn fetch<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    fetch_with(path, &[])
+}
+
+/// Fetch all remotes passing extra arguments like `--depth` to git fetch
+pub fn fetch_with<P>(path: P, args: &[String]) -> Result<()>
 where
     P: AsRef<Path>,
 {
     let path = format!("{}", path.as_ref().display());
-    debug!("Executing: git fetch --all --tags");
+    let command = format!("git fetch --all --tags {}", args.join(" "));
+    let command = command.trim_end();
+
+    debug!("Executing: {}", command);
+    remote_command(
+        process::null("git")
+            .args(["fetch", "--all", "--tags"])
+            .args(args)
+            .current_dir(&path),
+        command,
+    )
+    .map(|_| ())
+}
+
+/// Fetch the complete history of a shallow repository
+pub fn unshallow<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let output = process::piped("git")
+        .args(["rev-parse", "--is-shallow-repository"])
+        .current_dir(path.as_ref())
+        .output()?;
+
+    if String::from_utf8_lossy(&output.stdout).trim() != "true" {
+        return Ok(());
+    }
+
+    fetch_with(path, &["--unshallow".to_owned()])
+}
+
+/// Mark the remotes as promisors so that git fetches objects filtered by `filter` on demand
+pub fn set_partial_clone<P>(path: P, remotes: &[Remote], filter: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    for remote in remotes {
+        config(&path, &format!("remote.{}.promisor", remote.name), "true")?;
+        config(
+            &path,
+            &format!("remote.{}.partialclonefilter", remote.name),
+            filter,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Limit the working tree to the given paths
+pub fn sparse_checkout<P>(path: P, paths: &[String]) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let command = format!("git sparse-checkout set {}", paths.join(" "));
+    debug!("Executing: {}", command);
     process::null("git")
-        .args(["fetch", "--all", "--tags"])
-        .current_dir(&path)
+        .args(["sparse-checkout", "set"])
+        .args(paths)
+        .current_dir(path.as_ref())
         .status()
         .map_err(Into::into)
         .and_then(|st| match st.code() {
             Some(0) => Ok(()),
-            st => Err(anyhow!(
-                "command 'git fetch --all --tags' exited with return code: {:#?}",
-                st
-            )),
+            st => Err(Error::Command {
+                command: command.to_owned(),
+                code: st,
+                reason: None,
+            }),
         })
 }
 
-pub fn merge<P>(path: P, branch: &str) -> Result<()>
+/// Paths of the submodules defined in `.gitmodules`
+pub fn submodules<P>(path: P) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    if !path.as_ref().join(".gitmodules").is_file() {
+        return Ok(Vec::new());
+    }
+
+    let output = process::piped("git")
+        .args([
+            "config",
+            "--file",
+            ".gitmodules",
+            "--get-regexp",
+            r"^submodule\..*\.path$",
+        ])
+        .current_dir(path.as_ref())
+        .output()?;
+
+    // NOTE: git config exits with 1 if no key matches
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' ').map(|(_, path)| path.to_owned()))
+        .collect())
+}
+
+/// Initialize and update a submodule and its nested submodules
+pub fn update_submodule<P>(path: P, submodule: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let command = format!("git submodule update --init --recursive -- {}", submodule);
+    debug!("Executing: {}", command);
+    let mut git = process::null("git");
+
+    // NOTE: git only allows submodules on the local filesystem when the file protocol is allowed
+    // in the global config or on the command line, a setting in the clone's own config does not
+    // reach the submodule clones
+    let output = process::piped("git")
+        .args(["config", "--local", "--get", "protocol.file.allow"])
+        .current_dir(path.as_ref())
+        .output()?;
+    if output.status.success() {
+        let allow = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        git.args(["-c", &format!("protocol.file.allow={}", allow)]);
+    }
+
+    remote_command(
+        git.args([
+            "submodule",
+            "update",
+            "--init",
+            "--recursive",
+            "--",
+            submodule,
+        ])
+        .current_dir(path.as_ref()),
+        &command,
+    )
+    .map(|_| ())
+}
+
+/// Download the git lfs objects of the checked out commit
+pub fn lfs_pull<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    debug!("Executing: git lfs pull");
+    remote_command(
+        process::null("git")
+            .args(["lfs", "pull"])
+            .current_dir(path.as_ref()),
+        "git lfs pull",
+    )
+    .map(|_| ())
+}
+
+/// Check that git can be executed, for the operations that libgit2 does not implement
+pub fn check_installed() -> Result<()> {
+    debug!("Executing: git --version");
+    let status = process::null("git")
+        .arg("--version")
+        .status()
+        .context("failed to execute git, make sure that it is installed and in your PATH")?;
+
+    if status.success() {
+        return Ok(());
+    }
+
+    Err(Error::Command {
+        command: "git --version".to_owned(),
+        code: status.code(),
+        reason: None,
+    })
+}
+
+/// Pack every object of a repository, including the objects borrowed through its alternates
+pub fn repack<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    debug!("Executing: git repack -a -d");
+    let output = process::piped("git")
+        .args(["repack", "-a", "-d"])
+        .current_dir(path.as_ref())
+        .output()?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(Error::Command {
+        command: "git repack -a -d".to_owned(),
+        code: output.status.code(),
+        reason: Some(stderr.trim().to_owned()),
+    })
+}
+
+fn config<P>(path: P, key: &str, value: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    debug!("Executing: git config {} {}", key, value);
+    process::null("git")
+        .args(["config", key, value])
+        .current_dir(path.as_ref())
+        .status()
+        .map_err(Into::into)
+        .and_then(|st| match st.code() {
+            Some(0) => Ok(()),
+            st => Err(Error::Command {
+                command: format!("git config {} {}", key, value),
+                code: st,
+                reason: None,
+            }),
+        })
+}
+
+/// Read the name and url of every remote configured in a repository
+pub fn remotes<P>(path: P) -> Result<Vec<(String, String)>>
+where
+    P: AsRef<Path>,
+{
+    debug!("Executing: git config --get-regexp ^remote\\..*\\.url$");
+    let output = process::piped("git")
+        .args(["config", "--get-regexp", r"^remote\..*\.url$"])
+        .current_dir(path.as_ref())
+        .output()?;
+
+    // NOTE: git config exits with 1 when no key matches
+    if !output.status.success() && output.status.code() != Some(1) {
+        return Err(Error::Command {
+            command: "git config --get-regexp".to_owned(),
+            code: output.status.code(),
+            reason: None,
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (key, url) = line.split_once(' ')?;
+            let name = key.strip_prefix("remote.")?.strip_suffix(".url")?;
+            Some((name.to_owned(), url.to_owned()))
+        })
+        .collect())
+}
+
+/// Add, change the url of or remove a remote with `git remote <args>`
+fn remote<P>(path: P, args: &[&str]) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let command = format!("git remote {}", args.join(" "));
+    debug!("Executing: {}", command);
+    process::null("git")
+        .arg("remote")
+        .args(args)
+        .current_dir(path.as_ref())
+        .status()
+        .map_err(Into::into)
+        .and_then(|st| match st.code() {
+            Some(0) => Ok(()),
+            st => Err(Error::Command {
+                command: command.to_owned(),
+                code: st,
+                reason: None,
+            }),
+        })
+}
+
+pub fn add_remote<P>(path: P, name: &str, url: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    remote(path, &["add", name, url])
+}
+
+pub fn set_remote_url<P>(path: P, name: &str, url: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    remote(path, &["set-url", name, url])
+}
+
+pub fn remove_remote<P>(path: P, name: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    remote(path, &["remove", name])
+}
+
+/// Create or update a bare mirror of a remote url
+pub fn mirror<P>(path: P, url: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let (command, args, cwd): (String, Vec<String>, &Path) = if path.is_dir() {
+        (
+            "git fetch --prune origin".to_owned(),
+            vec!["fetch".into(), "--prune".into(), "origin".into()],
+            path,
+        )
+    } else {
+        let parent = path.parent().ok_or_else(|| {
+            Error::InvalidValue(format!(
+                "failed to get parent of mirror path: {}",
+                path.display()
+            ))
+        })?;
+        std::fs::create_dir_all(parent)?;
+
+        let target = format!("{}", path.display());
+        (
+            format!("git clone --mirror {} {}", url, target),
+            vec!["clone".into(), "--mirror".into(), url.into(), target],
+            parent,
+        )
+    };
+
+    debug!("Executing: {}", command);
+    remote_command(process::null("git").args(&args).current_dir(cwd), &command).map(|_| ())
+}
+
+/// Query the remote for the branch its HEAD points to
+pub fn default_branch<P>(path: P, remote: &str) -> Result<String>
 where
     P: AsRef<Path>,
 {
     let path = format!("{}", path.as_ref().display());
-    process::piped("git")
-        .args(["merge", branch])
+    let command = format!("git ls-remote --symref {} HEAD", remote);
+    debug!("Executing: {}", command);
+    let output = remote_command(
+        process::null("git")
+            .args(["ls-remote", "--symref", remote, "HEAD"])
+            .current_dir(&path),
+        &command,
+    )?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            line.strip_prefix("ref: refs/heads/")
+                .and_then(|rest| rest.split('\t').next())
+                .map(String::from)
+        })
+        .ok_or_else(|| Error::NoDefaultBranch(remote.to_owned()))
+}
+
+/// Create a local branch tracking the remote branch of the same name and check it out
+pub fn checkout<P>(path: P, remote: &str, branch: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = format!("{}", path.as_ref().display());
+    let upstream = format!("{}/{}", remote, branch);
+
+    debug!("Executing: git checkout -B {} --track {}", branch, upstream);
+    process::null("git")
+        .args(["checkout", "-B", branch, "--track", &upstream])
         .current_dir(&path)
         .status()
         .map_err(Into::into)
         .and_then(|st| match st.code() {
             Some(0) => Ok(()),
-            st => Err(anyhow!(
-                "command 'git merge {}' exited with return code: {:#?}",
-                branch,
-                st
-            )),
+            st => Err(Error::Command {
+                command: format!("git checkout -B {} --track {}", branch, upstream),
+                code: st,
+                reason: None,
+            }),
         })
 }
 
@@ -103,7 +453,15 @@ where
         .output()?;
 
     if !output.status.success() {
-        return Err(anyhow!("failed to get branch name"));
+        return Err(Error::Command {
+            command: "git rev-parse --abbrev-ref HEAD".to_owned(),
+            code: output.status.code(),
+            reason: String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .next()
+                .map(|l| l.trim().to_owned()),
+        })
+        .context("failed to get branch name");
     }
 
     let branch = String::from_utf8_lossy(&output.stdout).trim().to_owned();
@@ -127,15 +485,15 @@ where
 
     let upstream = String::from_utf8_lossy(&output.stdout).trim().to_owned();
 
-    // 3. Check if upstream is an ansestor of the current local branch
+    // 3. Check if the current local branch is an ansestor of upstream
     // If this check is true then we can fast-forward merge local up to upstream
     let status = process::null("git")
-        .args(["merge-base", "--is-ancestor", &upstream, &branch])
+        .args(["merge-base", "--is-ancestor", &branch, &upstream])
         .current_dir(&path)
         .status()?;
 
     if !status.success() {
-        return Err(anyhow!("local branch cannot be fast-forward merged"));
+        return Err(Error::NonFastForward);
     }
 
     // 4. Merge fast-forward
@@ -145,8 +503,522 @@ where
         .status()?;
 
     if !status.success() {
-        return Err(anyhow!("failure while performing fast-forward merge"));
+        return Err(Error::Command {
+            command: format!("git merge --ff-only {}", upstream),
+            code: status.code(),
+            reason: None,
+        });
+    }
+
+    Ok(())
+}
+
+/// Full name of the upstream of the checked out branch, `None` if there is no upstream
+fn upstream<P>(path: P) -> Result<Option<String>>
+where
+    P: AsRef<Path>,
+{
+    let path = format!("{}", path.as_ref().display());
+    debug!("Executing: git rev-parse --abbrev-ref --symbolic-full-name @{{upstream}}");
+    let output = process::piped("git")
+        .args([
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ])
+        .current_dir(&path)
+        .output()?;
+
+    // A detached HEAD or a branch without an upstream is not an error
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout).trim().to_owned(),
+    ))
+}
+
+/// Rebase the local commits of the checked out branch onto its upstream
+///
+/// A rebase that stops on conflicts is aborted so the repository is left as it was.
+pub fn rebase<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let upstream = match upstream(&path)? {
+        Some(upstream) => upstream,
+        None => return Ok(()),
+    };
+
+    let path = format!("{}", path.as_ref().display());
+    debug!("Executing: git rebase --no-autostash {}", upstream);
+    let status = process::null("git")
+        .args(["rebase", "--no-autostash", &upstream])
+        .current_dir(&path)
+        .status()?;
+
+    if !status.success() {
+        debug!("Executing: git rebase --abort");
+        let _ = process::null("git")
+            .args(["rebase", "--abort"])
+            .current_dir(&path)
+            .status();
+
+        return Err(Error::Command {
+            command: format!("git rebase --no-autostash {}", upstream),
+            code: status.code(),
+            reason: None,
+        })
+        .context(format!("failed to rebase local branch onto '{}'", upstream));
     }
 
     Ok(())
 }
+
+/// Reset the checked out branch, index and working tree to its upstream
+pub fn reset_hard<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let upstream = match upstream(&path)? {
+        Some(upstream) => upstream,
+        None => return Ok(()),
+    };
+
+    let path = format!("{}", path.as_ref().display());
+    debug!("Executing: git reset --hard {}", upstream);
+    process::null("git")
+        .args(["reset", "--hard", &upstream])
+        .current_dir(&path)
+        .status()
+        .map_err(Into::into)
+        .and_then(|st| match st.code() {
+            Some(0) => Ok(()),
+            st => Err(Error::Command {
+                command: format!("git reset --hard {}", upstream),
+                code: st,
+                reason: None,
+            }),
+        })
+}
+
+/// Stash the local changes of the working tree and index
+pub fn stash_save<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = format!("{}", path.as_ref().display());
+    debug!("Executing: git stash push -m 'repo: autostash'");
+    process::null("git")
+        .args(["stash", "push", "-m", "repo: autostash"])
+        .current_dir(&path)
+        .status()
+        .map_err(Into::into)
+        .and_then(|st| match st.code() {
+            Some(0) => Ok(()),
+            st => Err(Error::Command {
+                command: "git stash push".to_owned(),
+                code: st,
+                reason: None,
+            }),
+        })
+}
+
+/// Restore the most recently stashed changes and drop them from the stash
+pub fn stash_pop<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = format!("{}", path.as_ref().display());
+    debug!("Executing: git stash pop");
+    process::null("git")
+        .args(["stash", "pop"])
+        .current_dir(&path)
+        .status()
+        .map_err(Into::into)
+        .and_then(|st| match st.code() {
+            Some(0) => Ok(()),
+            st => Err(Error::Command {
+                command: "git stash pop".to_owned(),
+                code: st,
+                reason: None,
+            }),
+        })
+}
+
+/// Fetch all remotes and remove remote-tracking branches that no longer exist on the remote
+pub fn fetch_prune<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    fetch_with(path, &["--prune".to_owned()])
+}
+
+/// Local branches that track an upstream, with their upstream and whether it is gone
+fn tracking_branches<P>(path: P) -> Result<Vec<(String, String, bool)>>
+where
+    P: AsRef<Path>,
+{
+    let path = format!("{}", path.as_ref().display());
+    let format = "--format=%(refname:short)%00%(upstream:short)%00%(upstream:track)";
+
+    debug!("Executing: git for-each-ref {} refs/heads", format);
+    let output = process::piped("git")
+        .args(["for-each-ref", format, "refs/heads"])
+        .current_dir(&path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::Command {
+            command: format!("git for-each-ref {} refs/heads", format),
+            code: output.status.code(),
+            reason: None,
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let branch = fields.next()?;
+            let upstream = fields.next().filter(|u| !u.is_empty())?;
+            let gone = fields.next().is_some_and(|t| t.contains("gone"));
+            Some((branch.to_owned(), upstream.to_owned(), gone))
+        })
+        .collect())
+}
+
+/// Fast-forward every local branch, except the checked out one, whose upstream has moved
+///
+/// Branches that have diverged from their upstream are left as they are. Returns the names of the
+/// branches that were fast-forwarded.
+pub fn fast_forward_branches<P>(path: P) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let output = process::piped("git")
+        .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .current_dir(path)
+        .output()?;
+    let head = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+    let mut updated = Vec::new();
+    for (branch, upstream, gone) in tracking_branches(path)? {
+        if gone || branch == head {
+            continue;
+        }
+
+        let output = process::piped("git")
+            .args(["rev-parse", &branch, &upstream])
+            .current_dir(path)
+            .output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let oids: Vec<&str> = stdout.lines().collect();
+        if !output.status.success() || oids.len() != 2 || oids[0] == oids[1] {
+            continue;
+        }
+
+        let status = process::null("git")
+            .args(["merge-base", "--is-ancestor", oids[0], oids[1]])
+            .current_dir(path)
+            .status()?;
+        if !status.success() {
+            continue;
+        }
+
+        let reference = format!("refs/heads/{}", branch);
+        debug!(
+            "Executing: git update-ref {} {} {}",
+            reference, oids[1], oids[0]
+        );
+        let status = process::null("git")
+            .args(["update-ref", "-m", "repo: fast-forward", &reference])
+            .args([oids[1], oids[0]])
+            .current_dir(path)
+            .status()?;
+        if !status.success() {
+            return Err(Error::Command {
+                command: format!("git update-ref {}", reference),
+                code: status.code(),
+                reason: None,
+            })
+            .context(format!("failed to fast-forward branch '{}'", branch));
+        }
+
+        updated.push(branch);
+    }
+
+    Ok(updated)
+}
+
+/// Local branches whose upstream no longer exists
+pub fn gone_branches<P>(path: P) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    Ok(tracking_branches(path)?
+        .into_iter()
+        .filter(|(_, _, gone)| *gone)
+        .map(|(branch, _, _)| branch)
+        .collect())
+}
+
+/// Check if every commit of a local branch is reachable from HEAD
+pub fn is_merged<P>(path: P, branch: &str) -> Result<bool>
+where
+    P: AsRef<Path>,
+{
+    let path = format!("{}", path.as_ref().display());
+    let reference = format!("refs/heads/{}", branch);
+    debug!("Executing: git merge-base --is-ancestor {} HEAD", reference);
+    process::null("git")
+        .args(["merge-base", "--is-ancestor", &reference, "HEAD"])
+        .current_dir(&path)
+        .status()
+        .map_err(Into::into)
+        .and_then(|st| match st.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            st => Err(Error::Command {
+                command: format!("git merge-base --is-ancestor {} HEAD", reference),
+                code: st,
+                reason: None,
+            }),
+        })
+}
+
+/// Delete a local branch that is merged into HEAD
+pub fn delete_branch<P>(path: P, branch: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = format!("{}", path.as_ref().display());
+    debug!("Executing: git branch -d {}", branch);
+    process::null("git")
+        .args(["branch", "-d", branch])
+        .current_dir(&path)
+        .status()
+        .map_err(Into::into)
+        .and_then(|st| match st.code() {
+            Some(0) => Ok(()),
+            st => Err(Error::Command {
+                command: format!("git branch -d {}", branch),
+                code: st,
+                reason: None,
+            }),
+        })
+}
+
+pub fn status<P>(path: P) -> Result<Status>
+where
+    P: AsRef<Path>,
+{
+    let path = format!("{}", path.as_ref().display());
+    debug!("Executing: git status --porcelain=v2 --branch");
+    let output = process::piped("git")
+        .args(["status", "--porcelain=v2", "--branch"])
+        .current_dir(&path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::Command {
+            command: "git status --porcelain=v2 --branch".to_owned(),
+            code: output.status.code(),
+            reason: None,
+        });
+    }
+
+    let mut status = parse_status(&String::from_utf8_lossy(&output.stdout));
+
+    let output = process::piped("git")
+        .args(["rev-parse", "--absolute-git-dir"])
+        .current_dir(&path)
+        .output()?;
+    if output.status.success() {
+        let git_dir = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        status.operation = operation(Path::new(&git_dir)).map(String::from);
+    }
+
+    Ok(status)
+}
+
+/// Name of the operation in progress, found from the state files git leaves in its directory
+fn operation(git_dir: &Path) -> Option<&'static str> {
+    const STATES: &[(&str, &str)] = &[
+        ("rebase-merge", "rebase"),
+        ("rebase-apply", "rebase"),
+        ("MERGE_HEAD", "merge"),
+        ("CHERRY_PICK_HEAD", "cherry-pick"),
+        ("REVERT_HEAD", "revert"),
+        ("BISECT_LOG", "bisect"),
+    ];
+
+    STATES
+        .iter()
+        .find(|(file, _)| git_dir.join(file).exists())
+        .map(|(_, operation)| *operation)
+}
+
+fn parse_status(output: &str) -> Status {
+    let mut status = Status::default();
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            if head != "(detached)" {
+                status.branch = Some(head.to_owned());
+            }
+        } else if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(upstream.to_owned());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for count in ab.split_whitespace() {
+                if let Some(ahead) = count.strip_prefix('+') {
+                    status.ahead = ahead.parse().unwrap_or_default();
+                } else if let Some(behind) = count.strip_prefix('-') {
+                    status.behind = behind.parse().unwrap_or_default();
+                }
+            }
+        } else if line.starts_with("? ") {
+            status.untracked += 1;
+        } else if line.starts_with("1 ") || line.starts_with("2 ") || line.starts_with("u ") {
+            status.changed += 1;
+        }
+    }
+
+    status
+}
+
+/// Execute a git command that talks to a remote
+///
+/// The command is killed once the current attempt of [`network::retry`] times out. The cause from
+/// its error output is part of the error so that network failures can be recognized.
+pub(crate) fn remote_command(command: &mut Command, description: &str) -> Result<Output> {
+    let mut child = command
+        .envs(network::cli_env())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context(format!("failed to execute command '{}'", description))?;
+
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stdout = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        stdout.read_to_end(&mut buffer).map(|_| buffer)
+    });
+    let stderr = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        stderr.read_to_end(&mut buffer).map(|_| buffer)
+    });
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if network::expired() {
+            // NOTE: Only git is killed, processes it started like ssh exit once their pipes close
+            child.kill()?;
+            child.wait()?;
+            let timeout = network::current().timeout.unwrap_or_default();
+            return Err(Error::Timeout(timeout))
+                .context(format!("command '{}' was killed", description));
+        }
+
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    let output = Output {
+        status,
+        stdout: stdout.join().expect("failed to join stdout thread")?,
+        stderr: stderr.join().expect("failed to join stderr thread")?,
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // NOTE: git follows the cause of a failure with generic hints like checking access rights.
+        // A network failure can be reported on any of the error lines, like an early eof after a
+        // failed request.
+        let errors: Vec<&str> = stderr
+            .lines()
+            .filter(|l| l.starts_with("fatal:") || l.starts_with("error:"))
+            .map(str::trim)
+            .collect();
+        let reason = if errors.is_empty() {
+            stderr
+                .lines()
+                .rev()
+                .find(|l| !l.trim().is_empty())
+                .map(|l| l.trim().to_owned())
+        } else {
+            Some(errors.join("\n"))
+        };
+
+        return Err(Error::Command {
+            command: description.to_owned(),
+            code: output.status.code(),
+            reason,
+        });
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_porcelain_status() {
+        let output = "\
+# branch.oid 5d6e0a1c2b3f4e5d6c7b8a9f0e1d2c3b4a5f6e7d
+# branch.head main
+# branch.upstream origin/main
+# branch.ab +2 -3
+1 .M N... 100644 100644 100644 abc abc src/lib.rs
+2 R. N... 100644 100644 100644 abc abc R100 new.rs\told.rs
+? notes.txt
+";
+        let status = parse_status(output);
+
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!(status.ahead, 2);
+        assert_eq!(status.behind, 3);
+        assert_eq!(status.changed, 2);
+        assert_eq!(status.untracked, 1);
+    }
+
+    #[test]
+    fn parse_detached_status() {
+        let status = parse_status("# branch.oid abc\n# branch.head (detached)\n");
+
+        assert_eq!(status.branch, None);
+        assert_eq!(status.upstream, None);
+    }
+
+    #[test]
+    fn kill_timed_out_command() {
+        let _scope = network::Scope::new(network::Network {
+            timeout: Some(Duration::from_millis(100)),
+            ..network::Network::default()
+        });
+
+        let result = network::retry("sleep", || {
+            remote_command(process::null("sleep").arg("5"), "sleep 5").map(|_| ())
+        });
+        let error = result.unwrap_err();
+        assert!(matches!(error.root_cause(), Error::Timeout(_)));
+
+        let error = remote_command(
+            process::null("git").args(["ls-remote", "/missing/repository"]),
+            "git ls-remote",
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("does not appear to be a git repository"));
+        assert!(!network::is_retryable(&error));
+    }
+}
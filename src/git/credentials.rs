@@ -0,0 +1,281 @@
+use crate::ScpPath;
+use crate::{Error, Result};
+use git2::{Config, Cred, CredentialType, ErrorClass, ErrorCode};
+use git2_credentials::CredentialHandler;
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, fmt, path::PathBuf, str::FromStr};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Credentials>> = const { RefCell::new(None) };
+}
+
+/// Source of the credentials libgit2 uses to authenticate with a remote
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CredentialProvider {
+    /// Keys loaded in the running ssh-agent
+    SshAgent,
+    /// The `ssh_key` of the host alias matching the remote's host
+    SshKey,
+    /// The git credential helpers configured in the git config
+    Helper,
+    /// A token read from the `token_env` environment variable of the host alias matching the
+    /// remote's host
+    Token,
+    /// Everything git2_credentials tries: ssh-agent, default ssh keys and credential helpers
+    Default,
+}
+
+impl fmt::Display for CredentialProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = match self {
+            CredentialProvider::SshAgent => "ssh-agent",
+            CredentialProvider::SshKey => "ssh-key",
+            CredentialProvider::Helper => "helper",
+            CredentialProvider::Token => "token",
+            CredentialProvider::Default => "default",
+        };
+
+        write!(f, "{}", result)
+    }
+}
+
+impl FromStr for CredentialProvider {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ssh-agent" => Ok(CredentialProvider::SshAgent),
+            "ssh-key" => Ok(CredentialProvider::SshKey),
+            "helper" => Ok(CredentialProvider::Helper),
+            "token" => Ok(CredentialProvider::Token),
+            "default" => Ok(CredentialProvider::Default),
+            _ => Err(Error::InvalidValue(format!(
+                "'{}' is not a credential provider: [ssh-agent, ssh-key, helper, token, default]",
+                s
+            ))),
+        }
+    }
+}
+
+/// Credentials of the hosts a repository's remotes live on
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HostCredentials {
+    /// Host name, optionally with a port
+    pub host: String,
+    /// Private ssh key used for the host
+    pub ssh_key: Option<PathBuf>,
+    /// Environment variable holding an access token for the host
+    pub token_env: Option<String>,
+}
+
+/// Providers tried in order when libgit2 needs credentials
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Credentials {
+    pub providers: Vec<CredentialProvider>,
+    pub hosts: Vec<HostCredentials>,
+}
+
+impl Default for Credentials {
+    fn default() -> Self {
+        Self {
+            providers: vec![CredentialProvider::Default],
+            hosts: Vec::new(),
+        }
+    }
+}
+
+impl Credentials {
+    fn host(&self, url: &str) -> Option<&HostCredentials> {
+        let host = match url::Url::parse(url) {
+            Ok(url) => match (url.host_str(), url.port()) {
+                (Some(host), Some(port)) => format!("{}:{}", host, port),
+                (Some(host), None) => host.to_owned(),
+                (None, _) => return None,
+            },
+            Err(_) => ScpPath::parse(url).ok()?.host,
+        };
+
+        // NOTE: An alias without a port matches the host on any port
+        self.hosts.iter().find(|h| {
+            h.host.eq_ignore_ascii_case(&host)
+                || (!h.host.contains(':')
+                    && host
+                        .split(':')
+                        .next()
+                        .is_some_and(|name| h.host.eq_ignore_ascii_case(name)))
+        })
+    }
+}
+
+/// Uses credentials for the libgit2 operations of the current thread until dropped
+pub(crate) struct Scope;
+
+impl Scope {
+    pub(crate) fn new(credentials: Credentials) -> Self {
+        CURRENT.with(|current| *current.borrow_mut() = Some(credentials));
+        Scope
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.borrow_mut().take());
+    }
+}
+
+/// Credentials of the current thread's [`Scope`], or the default providers outside of one
+pub(crate) fn current() -> Credentials {
+    CURRENT.with(|current| current.borrow().clone().unwrap_or_default())
+}
+
+/// Hands out the credentials of each provider in turn as libgit2 asks again after every failure
+pub(crate) struct CredentialState {
+    credentials: Credentials,
+    config: Config,
+    handler: CredentialHandler,
+    index: usize,
+    /// Reads the environment variables holding tokens
+    env: fn(&str) -> Option<String>,
+}
+
+impl CredentialState {
+    pub(crate) fn new(credentials: Credentials) -> Result<Self> {
+        Self::with_env(credentials, |name| std::env::var(name).ok())
+    }
+
+    fn with_env(credentials: Credentials, env: fn(&str) -> Option<String>) -> Result<Self> {
+        let config = Config::open_default()?;
+        let handler = CredentialHandler::new(Config::open_default()?);
+
+        Ok(Self {
+            credentials,
+            config,
+            handler,
+            index: 0,
+            env,
+        })
+    }
+
+    pub(crate) fn next(
+        &mut self,
+        url: &str,
+        username: Option<&str>,
+        allowed: CredentialType,
+    ) -> std::result::Result<Cred, git2::Error> {
+        let user = username.unwrap_or("git");
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(user);
+        }
+
+        let ssh = allowed.contains(CredentialType::SSH_KEY);
+        let plaintext = allowed.contains(CredentialType::USER_PASS_PLAINTEXT);
+        let host = self.credentials.host(url).cloned();
+
+        while let Some(provider) = self.credentials.providers.get(self.index).copied() {
+            // NOTE: The default provider keeps its own state and is asked until it runs out
+            if provider == CredentialProvider::Default {
+                match self.handler.try_next_credential(url, username, allowed) {
+                    Ok(cred) => return Ok(cred),
+                    Err(_) => {
+                        self.index += 1;
+                        continue;
+                    }
+                }
+            }
+
+            self.index += 1;
+            let cred = match provider {
+                CredentialProvider::SshAgent if ssh => Cred::ssh_key_from_agent(user).ok(),
+                CredentialProvider::SshKey if ssh => host
+                    .as_ref()
+                    .and_then(|h| h.ssh_key.as_ref())
+                    .and_then(|key| Cred::ssh_key(user, None, key, None).ok()),
+                CredentialProvider::Helper if plaintext => {
+                    Cred::credential_helper(&self.config, url, username).ok()
+                }
+                CredentialProvider::Token if plaintext => host
+                    .as_ref()
+                    .and_then(|h| h.token_env.as_ref())
+                    .and_then(|name| (self.env)(name))
+                    .and_then(|token| Cred::userpass_plaintext(user, &token).ok()),
+                _ => None,
+            };
+
+            if let Some(cred) = cred {
+                trace!("Using credentials from provider: {}", provider);
+                return Ok(cred);
+            }
+        }
+
+        Err(git2::Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Callback,
+            format!(
+                "no credentials found for '{}' with providers: [{}]",
+                url,
+                self.credentials
+                    .providers
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials() -> Credentials {
+        Credentials {
+            providers: vec![CredentialProvider::Token],
+            hosts: vec![
+                HostCredentials {
+                    host: "gitlab.com".to_owned(),
+                    ssh_key: None,
+                    token_env: Some("GITLAB_TOKEN".to_owned()),
+                },
+                HostCredentials {
+                    host: "git.work.com:8443".to_owned(),
+                    ssh_key: Some(PathBuf::from("/keys/work")),
+                    token_env: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn host_of_url() {
+        let credentials = credentials();
+        let host = |url| credentials.host(url).map(|h| h.host.as_str());
+
+        assert_eq!(host("https://GitLab.com/user/repo"), Some("gitlab.com"));
+        assert_eq!(
+            host("ssh://git@gitlab.com:22/user/repo"),
+            Some("gitlab.com")
+        );
+        assert_eq!(host("git@gitlab.com:user/repo"), Some("gitlab.com"));
+        assert_eq!(
+            host("https://git.work.com:8443/repo"),
+            Some("git.work.com:8443")
+        );
+        assert_eq!(host("https://git.work.com/repo"), None);
+        assert_eq!(host("https://github.com/user/repo"), None);
+    }
+
+    #[test]
+    fn token_from_env() {
+        let env = |name: &str| (name == "GITLAB_TOKEN").then(|| "secret".to_owned());
+        let mut state = CredentialState::with_env(credentials(), env).unwrap();
+        let url = "https://gitlab.com/user/repo";
+        let cred = state.next(url, None, CredentialType::USER_PASS_PLAINTEXT);
+        assert!(cred.is_ok());
+
+        // Every provider has been tried once
+        let cred = state.next(url, None, CredentialType::USER_PASS_PLAINTEXT);
+        assert!(cred.is_err());
+    }
+}
@@ -1,21 +1,41 @@
-use crate::{util::process, Remote};
-use anyhow::{anyhow, Result};
+use crate::{
+    git::{
+        credentials::{self, CredentialState},
+        network, Status,
+    },
+    progress::{self, Stage},
+    util::process,
+    Remote,
+};
+use crate::{Error, Result};
 use git2::{
-    build::{CheckoutBuilder, RepoBuilder},
-    AutotagOption, Branch, BranchType, Config, FetchOptions, MergeAnalysis, MergeOptions, Object,
-    ObjectType, ProxyOptions, RemoteCallbacks, Repository, ResetType,
+    build::CheckoutBuilder, AnnotatedCommit, AutotagOption, Branch, BranchType, Config, Direction,
+    ErrorCode, FetchOptions, FetchPrune, MergeAnalysis, MergeOptions, Object, ObjectType,
+    ProxyOptions, RemoteCallbacks, Repository, RepositoryState, ResetType, Signature,
+    Status as FileStatus, StatusOptions, SubmoduleUpdateOptions,
 };
 use git2_credentials::CredentialHandler;
-use std::path::Path;
+use std::{convert::TryFrom, os::raw::c_int, path::Path, time::Duration};
+
+// NOTE: git2 does not expose the server timeouts of libgit2 yet
+extern "C" {
+    fn git_libgit2_opts(option: c_int, ...) -> c_int;
+}
+
+const GIT_OPT_SET_SERVER_CONNECT_TIMEOUT: c_int = 39;
+const GIT_OPT_SET_SERVER_TIMEOUT: c_int = 41;
 
 pub fn init<P>(path: P, remotes: &[Remote]) -> Result<()>
 where
     P: AsRef<Path>,
 {
     let path = path.as_ref();
-    let parent = path
-        .parent()
-        .ok_or_else(|| anyhow!("failed to get parent of repo path: {}", path.display()))?;
+    let parent = path.parent().ok_or_else(|| {
+        Error::InvalidValue(format!(
+            "failed to get parent of repo path: {}",
+            path.display()
+        ))
+    })?;
 
     if !parent.is_dir() {
         std::fs::create_dir_all(parent)?;
@@ -29,50 +49,99 @@ where
     Ok(())
 }
 
-pub fn clone<P>(path: P, remotes: &[Remote]) -> Result<()>
+/// Read the name and url of every remote configured in a repository
+pub fn remotes<P>(path: P) -> Result<Vec<(String, String)>>
 where
     P: AsRef<Path>,
 {
-    let path = path.as_ref();
-    let parent = path
-        .parent()
-        .ok_or_else(|| anyhow!("failed to get parent of repo path: {}", path.display()))?;
+    let repo = Repository::open(path)?;
+    let mut result = Vec::new();
 
-    if !parent.is_dir() {
-        std::fs::create_dir_all(parent)?;
+    for name in repo.remotes()?.iter().flatten() {
+        let remote = repo.find_remote(name)?;
+        if let Some(url) = remote.url() {
+            result.push((name.to_owned(), url.to_owned()));
+        }
     }
 
-    let url = remotes.first().map(|r| r.url.as_str()).unwrap();
+    Ok(result)
+}
 
-    let config = Config::open_default()?;
-    let mut credentials = CredentialHandler::new(config);
-    let mut remote_callbacks = RemoteCallbacks::new();
-    remote_callbacks.credentials(move |url, username, allowed| {
-        credentials.try_next_credential(url, username, allowed)
-    });
+pub fn add_remote<P>(path: P, name: &str, url: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    Repository::open(path)?.remote(name, url)?;
+    Ok(())
+}
 
-    let mut proxy = ProxyOptions::new();
-    proxy.auto();
+pub fn set_remote_url<P>(path: P, name: &str, url: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    Repository::open(path)?.remote_set_url(name, url)?;
+    Ok(())
+}
 
-    let mut fetch = FetchOptions::new();
-    fetch
-        .proxy_options(proxy)
-        .remote_callbacks(remote_callbacks);
+pub fn remove_remote<P>(path: P, name: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    Repository::open(path)?.remote_delete(name)?;
+    Ok(())
+}
 
-    let checkout = CheckoutBuilder::new();
-    let repo = RepoBuilder::new()
-        .with_checkout(checkout)
-        .fetch_options(fetch)
-        .clone(url, path)?;
+/// Limit the time libgit2 waits for a remote to connect and to send or receive data
+///
+/// libgit2 applies these timeouts to the sockets of http(s) and git remotes. For ssh remotes they
+/// only cover connecting, see [`can_time_out`](crate::git::can_time_out).
+pub fn set_server_timeouts(timeout: Duration) {
+    let millis = timeout_millis(timeout);
 
-    for remote in remotes.iter().skip(1) {
-        repo.remote(&remote.name, remote.url.as_str())?;
+    // SAFETY: Both options take a single int and only store it in a global of libgit2
+    unsafe {
+        git_libgit2_opts(GIT_OPT_SET_SERVER_CONNECT_TIMEOUT, millis);
+        git_libgit2_opts(GIT_OPT_SET_SERVER_TIMEOUT, millis);
     }
+}
 
-    Ok(())
+/// Timeout in the milliseconds libgit2 expects, saturating at the largest timeout it supports
+pub(crate) fn timeout_millis(timeout: Duration) -> c_int {
+    c_int::try_from(timeout.as_millis()).unwrap_or(c_int::MAX)
 }
 
 pub fn fetch<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    fetch_with(path, 0, false)
+}
+
+/// Fetch all remotes and remove remote-tracking branches that no longer exist on the remote
+pub fn fetch_prune<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    fetch_with(path, 0, true)
+}
+
+/// Fetch the complete history of a shallow repository
+pub fn unshallow<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    if !Repository::open(&path)?.is_shallow() {
+        return Ok(());
+    }
+
+    // NOTE: libgit2 treats the maximum depth as a request to unshallow the repository
+    fetch_with(path, i32::MAX, false)
+}
+
+/// Fetch all remotes. A `depth` of zero fetches the full history
+///
+/// With `prune` the remote-tracking branches that no longer exist on the remote are removed.
+pub fn fetch_with<P>(path: P, depth: i32, prune: bool) -> Result<()>
 where
     P: AsRef<Path>,
 {
@@ -83,20 +152,9 @@ where
         let remote_name = iter.expect("remote name is not utf-8");
         let mut remote = repo.find_remote(remote_name)?;
 
-        let config = Config::open_default()?;
-        let mut credentials = CredentialHandler::new(config);
-        let mut remote_callbacks = RemoteCallbacks::new();
-        remote_callbacks.credentials(move |url, username, allowed| {
-            credentials.try_next_credential(url, username, allowed)
-        });
-
-        let mut proxy = ProxyOptions::new();
-        proxy.auto();
-
-        let mut fetch = FetchOptions::new();
+        let mut fetch = fetch_options()?;
         fetch
-            .proxy_options(proxy)
-            .remote_callbacks(remote_callbacks)
+            .depth(depth)
             .download_tags(AutotagOption::All)
             .update_fetchhead(true);
 
@@ -108,11 +166,127 @@ where
 
         // Update references in remotes namespace
         remote.update_tips(None, true, AutotagOption::Unspecified, None)?;
+
+        // NOTE: Pruning compares against the refs advertised by the remote during the download
+        if prune {
+            remote.prune(None)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Create or update a bare mirror of a remote url
+pub fn mirror<P>(path: P, url: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let repo = if path.is_dir() {
+        Repository::open_bare(path)?
+    } else {
+        std::fs::create_dir_all(path)?;
+        let repo = Repository::init_bare(path)?;
+        repo.remote_with_fetch("origin", url, "+refs/*:refs/*")?;
+        repo.config()?.set_bool("remote.origin.mirror", true)?;
+        repo
+    };
+
+    let mut remote = repo.find_remote("origin")?;
+    let mut fetch = fetch_options()?;
+    fetch
+        .prune(FetchPrune::On)
+        .download_tags(AutotagOption::All);
+    remote.fetch(&[] as &[&str], Some(&mut fetch), None)?;
+
+    // Keep HEAD pointing at the default branch of the remote so clones can check it out
+    if let Ok(branch) = remote.default_branch() {
+        if let Some(branch) = branch.as_str() {
+            repo.set_head(branch)?;
+        }
     }
 
     Ok(())
 }
 
+/// Query the remote for the branch its HEAD points to
+pub fn default_branch<P>(path: P, remote_name: &str) -> Result<String>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let mut remote = repo.find_remote(remote_name)?;
+    let connection = remote.connect_auth(
+        Direction::Fetch,
+        Some(remote_callbacks()?),
+        Some(proxy_options()),
+    )?;
+
+    let branch = connection.default_branch()?;
+    branch
+        .as_str()
+        .and_then(|b| b.strip_prefix("refs/heads/"))
+        .map(String::from)
+        .ok_or_else(|| Error::NoDefaultBranch(remote_name.to_owned()))
+}
+
+/// Name of the branch the HEAD of a bare repository points to
+pub fn head_branch<P>(path: P) -> Result<String>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open_bare(path)?;
+    let head = repo.find_reference("HEAD")?;
+    head.symbolic_target()
+        .and_then(|t| t.strip_prefix("refs/heads/"))
+        .map(String::from)
+        .ok_or(Error::DetachedHead)
+}
+
+/// Point the remote tracking branches of a remote at the branches of a mirror
+///
+/// The objects must be reachable through the alternates of the repository. Fetching afterwards
+/// only downloads what the mirror does not have.
+pub fn seed_from_mirror<P, M>(path: P, remote: &str, mirror: M) -> Result<()>
+where
+    P: AsRef<Path>,
+    M: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let mirror = Repository::open_bare(mirror)?;
+
+    for reference in mirror.references_glob("refs/heads/*")?.flatten() {
+        if let (Some(name), Some(oid)) = (reference.name(), reference.target()) {
+            let name = name.replacen("refs/heads/", &format!("refs/remotes/{}/", remote), 1);
+            repo.reference(&name, oid, true, "seed from mirror")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a local branch tracking the remote branch of the same name and check it out
+pub fn checkout<P>(path: P, remote: &str, branch: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let upstream = format!("{}/{}", remote, branch);
+    let commit = repo
+        .find_branch(&upstream, BranchType::Remote)?
+        .get()
+        .peel_to_commit()?;
+
+    let mut local = repo.branch(branch, &commit, true)?;
+    local.set_upstream(Some(&upstream))?;
+
+    let mut checkout = checkout_builder();
+    repo.checkout_tree(commit.as_object(), Some(checkout.force()))?;
+    repo.set_head(&format!("refs/heads/{}", branch))?;
+
+    Ok(())
+}
+
 pub fn inital_merge<P>(path: P, branch: &str) -> Result<()>
 where
     P: AsRef<Path>,
@@ -120,7 +294,7 @@ where
     let repo = Repository::open(path)?;
 
     let reference = repo.resolve_reference_from_short_name(branch)?;
-    let mut checkout = CheckoutBuilder::new();
+    let mut checkout = checkout_builder();
     repo.reset(
         &reference.peel(ObjectType::Commit)?,
         ResetType::Hard,
@@ -139,7 +313,7 @@ where
     // 1. Get head branch
     let head = repo.head()?;
     if !head.is_branch() {
-        return Err(anyhow!("HEAD is not pointing to a branch"));
+        return Err(Error::DetachedHead);
     }
 
     let branch = Branch::wrap(head);
@@ -150,10 +324,397 @@ where
     if MergeAnalysis::is_fast_forward(&analysis) {
         let upstream_oid = upstream_commit.id();
         let upstream_object = repo.find_object(upstream_oid, None)?;
-        repo.checkout_tree(&upstream_object, None)?;
+
+        // NOTE: A safe checkout refuses to overwrite local changes instead of discarding them
+        let mut checkout = checkout_builder();
+        repo.checkout_tree(&upstream_object, Some(checkout.safe()))?;
         repo.head()?
             .set_target(upstream_oid, "fast-forward merge")?;
+    } else if !MergeAnalysis::is_up_to_date(&analysis) {
+        return Err(Error::NonFastForward);
     }
 
     Ok(())
 }
+
+/// Upstream commit of the checked out branch, `None` if HEAD is detached or has no upstream
+fn upstream_commit(repo: &Repository) -> Result<Option<AnnotatedCommit<'_>>> {
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Ok(None);
+    }
+
+    let upstream = match Branch::wrap(head).upstream() {
+        Ok(upstream) => upstream,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Some(repo.reference_to_annotated_commit(upstream.get())?))
+}
+
+/// Rebase the local commits of the checked out branch onto its upstream
+///
+/// A rebase that stops on conflicts is aborted so the repository is left as it was.
+pub fn rebase<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let signature = signature(&repo)?;
+    let upstream = match upstream_commit(&repo)? {
+        Some(upstream) => upstream,
+        None => return Ok(()),
+    };
+
+    let branch = repo.reference_to_annotated_commit(&repo.head()?)?;
+    let mut rebase = repo.rebase(Some(&branch), Some(&upstream), None, None)?;
+    while let Some(operation) = rebase.next() {
+        if let Err(e) = operation {
+            rebase.abort()?;
+            return Err(e.into());
+        }
+
+        if repo.index()?.has_conflicts() {
+            rebase.abort()?;
+            return Err(Error::RebaseConflict);
+        }
+
+        match rebase.commit(None, &signature, None) {
+            Ok(_) => {}
+            // The change is already in upstream, nothing to commit
+            Err(e) if e.code() == ErrorCode::Applied => {}
+            Err(e) => {
+                rebase.abort()?;
+                return Err(e.into());
+            }
+        }
+    }
+
+    rebase.finish(Some(&signature))?;
+    Ok(())
+}
+
+/// Reset the checked out branch, index and working tree to its upstream
+pub fn reset_hard<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let upstream = match upstream_commit(&repo)? {
+        Some(upstream) => upstream.id(),
+        None => return Ok(()),
+    };
+
+    let object = repo.find_object(upstream, None)?;
+    let mut checkout = checkout_builder();
+    repo.reset(&object, ResetType::Hard, Some(checkout.force()))?;
+
+    Ok(())
+}
+
+/// Stash the local changes of the working tree and index
+pub fn stash_save<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let mut repo = Repository::open(path)?;
+    let signature = signature(&repo)?;
+    repo.stash_save(&signature, "repo: autostash", None)?;
+    Ok(())
+}
+
+/// Restore the most recently stashed changes and drop them from the stash
+pub fn stash_pop<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let mut repo = Repository::open(path)?;
+    repo.stash_pop(0, None)?;
+    Ok(())
+}
+
+/// Signature from the git config, with a fallback for machines without a configured identity
+fn signature(repo: &Repository) -> Result<Signature<'static>> {
+    repo.signature()
+        .or_else(|_| Signature::now("repo", "repo@localhost"))
+        .map_err(Into::into)
+}
+
+/// Fast-forward every local branch, except the checked out one, whose upstream has moved
+///
+/// Branches that have diverged from their upstream are left as they are. Returns the names of the
+/// branches that were fast-forwarded.
+pub fn fast_forward_branches<P>(path: P) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+
+    let mut updated = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (mut branch, _) = branch?;
+        if branch.is_head() {
+            continue;
+        }
+
+        let upstream = match branch.upstream() {
+            Ok(upstream) => upstream,
+            Err(_) => continue,
+        };
+
+        let (local, remote) = match (branch.get().target(), upstream.get().target()) {
+            (Some(local), Some(remote)) if local != remote => (local, remote),
+            _ => continue,
+        };
+
+        if !repo.graph_descendant_of(remote, local)? {
+            continue;
+        }
+
+        let name = branch.name()?.unwrap_or_default().to_owned();
+        branch.get_mut().set_target(remote, "repo: fast-forward")?;
+        updated.push(name);
+    }
+
+    Ok(updated)
+}
+
+/// Local branches whose upstream no longer exists
+pub fn gone_branches<P>(path: P) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+
+    let mut gone = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let reference = match branch.get().name() {
+            Some(reference) => reference,
+            None => continue,
+        };
+
+        // NOTE: The upstream name is read from the branch config, its ref may no longer exist
+        let upstream = match repo.branch_upstream_name(reference) {
+            Ok(upstream) => upstream,
+            Err(_) => continue,
+        };
+
+        if let Some(upstream) = upstream.as_str() {
+            if repo.find_reference(upstream).is_err() {
+                gone.push(branch.name()?.unwrap_or_default().to_owned());
+            }
+        }
+    }
+
+    Ok(gone)
+}
+
+/// Delete a local branch even if it is not merged
+/// Check if every commit of a local branch is reachable from HEAD
+pub fn is_merged<P>(path: P, branch: &str) -> Result<bool>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let branch = repo.find_branch(branch, BranchType::Local)?;
+    branch_is_merged(&repo, &branch)
+}
+
+fn branch_is_merged(repo: &Repository, branch: &Branch) -> Result<bool> {
+    let head = repo.head()?.peel_to_commit()?.id();
+    let tip = branch.get().peel_to_commit()?.id();
+    Ok(head == tip || repo.graph_descendant_of(head, tip)?)
+}
+
+/// Delete a local branch that is merged into HEAD
+pub fn delete_branch<P>(path: P, branch: &str) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let mut reference = repo.find_branch(branch, BranchType::Local)?;
+    if !branch_is_merged(&repo, &reference)? {
+        return Err(Error::NotMerged(branch.to_owned()));
+    }
+
+    reference.delete()?;
+    Ok(())
+}
+
+pub fn status<P>(path: P) -> Result<Status>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let mut status = Status::default();
+
+    match repo.head() {
+        Ok(head) if head.is_branch() => {
+            status.branch = head.shorthand().map(String::from);
+
+            let branch = Branch::wrap(head);
+            if let Ok(upstream) = branch.upstream() {
+                status.upstream = upstream.name()?.map(String::from);
+
+                if let (Some(local), Some(remote)) =
+                    (branch.get().target(), upstream.get().target())
+                {
+                    let (ahead, behind) = repo.graph_ahead_behind(local, remote)?;
+                    status.ahead = ahead;
+                    status.behind = behind;
+                }
+            }
+        }
+        Ok(_) => {}
+        Err(e) if e.code() == ErrorCode::UnbornBranch => {
+            // A freshly initialized repository has a HEAD pointing at a branch without commits
+            let head = repo.find_reference("HEAD")?;
+            status.branch = head
+                .symbolic_target()
+                .map(|t| t.trim_start_matches("refs/heads/").to_owned());
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    status.operation = match repo.state() {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some("merge"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some("revert"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("cherry-pick"),
+        RepositoryState::Bisect => Some("bisect"),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge
+        | RepositoryState::ApplyMailbox
+        | RepositoryState::ApplyMailboxOrRebase => Some("rebase"),
+    }
+    .map(String::from);
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let file = entry.status();
+        if file == FileStatus::WT_NEW {
+            status.untracked += 1;
+        } else if !file.is_empty() && !file.is_ignored() {
+            status.changed += 1;
+        }
+    }
+
+    Ok(status)
+}
+
+/// Initialize and update every submodule recursively
+///
+/// Returns the outcome for each submodule by its path. Nested submodules are only updated if
+/// their parent succeeded.
+pub fn update_submodules<P>(path: P) -> Result<Vec<(String, Result<()>)>>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(path)?;
+    let mut results = Vec::new();
+    update_submodules_in(&repo, "", &mut results)?;
+    Ok(results)
+}
+
+fn update_submodules_in(
+    repo: &Repository,
+    prefix: &str,
+    results: &mut Vec<(String, Result<()>)>,
+) -> Result<()> {
+    for mut submodule in repo.submodules()? {
+        let name = format!("{}{}", prefix, submodule.path().display());
+
+        let nested = network::retry("submodule update", || {
+            let mut options = SubmoduleUpdateOptions::new();
+            options.fetch(fetch_options()?);
+            Ok(submodule.update(true, Some(&mut options))?)
+        })
+        .and_then(|_| Ok(submodule.open()?));
+
+        match nested {
+            Ok(nested) => {
+                results.push((name.clone(), Ok(())));
+                if let Err(e) = update_submodules_in(&nested, &format!("{}/", name), results) {
+                    results.push((name, Err(e)));
+                }
+            }
+            Err(e) => results.push((name, Err(e))),
+        }
+    }
+
+    Ok(())
+}
+
+fn remote_callbacks<'a>() -> Result<RemoteCallbacks<'a>> {
+    let mut credentials = CredentialState::new(credentials::current())?;
+    let mut remote_callbacks = RemoteCallbacks::new();
+    remote_callbacks.credentials(move |url, username, allowed| {
+        // NOTE: Authentication happens before any progress is reported, a remote that keeps
+        // rejecting the credentials would otherwise never reach the timeout
+        if network::expired() {
+            return Err(git2::Error::from_str("timed out while authenticating"));
+        }
+        credentials.next(url, username, allowed)
+    });
+
+    Ok(remote_callbacks)
+}
+
+fn proxy_options<'a>() -> ProxyOptions<'a> {
+    let mut proxy = ProxyOptions::new();
+    proxy.auto();
+    proxy
+}
+
+fn fetch_options<'a>() -> Result<FetchOptions<'a>> {
+    let mut callbacks = remote_callbacks()?;
+    callbacks
+        .transfer_progress(|stats| {
+            let stage = if stats.received_objects() < stats.total_objects() {
+                Stage::Receiving {
+                    received: stats.received_objects(),
+                    total: stats.total_objects(),
+                    bytes: stats.received_bytes(),
+                }
+            } else {
+                Stage::Resolving {
+                    indexed: stats.indexed_deltas(),
+                    total: stats.total_deltas(),
+                }
+            };
+            progress::report(stage);
+            !network::expired()
+        })
+        .sideband_progress(|data| {
+            let message = String::from_utf8_lossy(data);
+            // NOTE: Remotes redraw their progress with carriage returns, keep the latest part
+            if let Some(line) = message
+                .split(['\r', '\n'])
+                .rev()
+                .find(|l| !l.trim().is_empty())
+            {
+                progress::report(Stage::Remote(line.trim()));
+            }
+            !network::expired()
+        });
+
+    let mut fetch = FetchOptions::new();
+    fetch
+        .proxy_options(proxy_options())
+        .remote_callbacks(callbacks);
+
+    Ok(fetch)
+}
+
+fn checkout_builder<'a>() -> CheckoutBuilder<'a> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout.progress(|_, current, total| {
+        progress::report(Stage::Checkout { current, total });
+    });
+    checkout
+}
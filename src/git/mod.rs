@@ -1,43 +1,1365 @@
-use crate::Remote;
-use anyhow::Result;
-use std::path::Path;
+pub use backend::Backend;
+pub use credentials::{CredentialProvider, Credentials, HostCredentials};
+pub use network::{is_retryable, Network};
+pub use strategy::UpdateStrategy;
 
-pub fn clone<P>(path: P, branch: &str, remotes: &[Remote], use_cli: bool) -> Result<()>
+use crate::{error::Context, Error, Result};
+use crate::{Remote, ScpPath};
+use std::path::{Path, PathBuf};
+
+/// Snapshot of the git state of a repository's working tree
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Status {
+    /// Name of the checked out branch, `None` if HEAD is detached
+    pub branch: Option<String>,
+    /// Name of the upstream branch tracked by the checked out branch
+    pub upstream: Option<String>,
+    /// Number of commits the branch is ahead of its upstream
+    pub ahead: usize,
+    /// Number of commits the branch is behind its upstream
+    pub behind: usize,
+    /// Number of tracked files that are modified, staged, deleted or conflicted
+    pub changed: usize,
+    /// Number of untracked files
+    pub untracked: usize,
+    /// Git operation that has been started but not finished, like a `rebase` or `merge`
+    pub operation: Option<String>,
+}
+
+impl Status {
+    /// Reason the working tree cannot be updated safely with `strategy`, `None` if it can
+    ///
+    /// Fetching never touches the working tree and a hard reset discards local changes on purpose,
+    /// otherwise local changes have to be stashed with `autostash`.
+    pub fn update_blocker(&self, strategy: UpdateStrategy, autostash: bool) -> Option<String> {
+        if strategy == UpdateStrategy::Fetch {
+            return None;
+        }
+
+        if let Some(operation) = &self.operation {
+            return Some(format!("{} in progress", operation));
+        }
+
+        if self.branch.is_none() {
+            return Some("detached HEAD".to_owned());
+        }
+
+        if self.changed > 0 && strategy != UpdateStrategy::ResetHard && !autostash {
+            return Some("uncommitted changes, commit them or enable autostash".to_owned());
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct CloneOptions {
+    /// Branch to check out. If `None` the branch pointed to by the HEAD of the first remote is used
+    pub branch: Option<String>,
+    /// Bare mirrors by remote name. The clone borrows their objects through git alternates
+    pub references: Vec<(String, PathBuf)>,
+    /// Number of commits to fetch, `None` fetches the full history
+    pub depth: Option<u32>,
+    /// Partial clone filter spec like `blob:none`
+    pub filter: Option<String>,
+    /// Paths of a sparse checkout, empty checks out everything
+    pub sparse: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PullOptions {
+    /// How the checked out branch is brought up to date with its upstream
+    pub strategy: UpdateStrategy,
+    /// Stash local changes while updating the branch
+    pub autostash: bool,
+    /// Prune remote-tracking branches and fast-forward every local branch that tracks an upstream
+    pub all_branches: bool,
+}
+
+/// Local branches touched by an update
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct BranchReport {
+    /// Branches other than the checked out one that were fast-forwarded
+    pub updated: Vec<String>,
+    /// Branches whose upstream no longer exists on the remote
+    pub gone: Vec<String>,
+}
+
+/// Change made to the git remotes of a repository to match its configured remotes
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RemoteChange {
+    /// Remote was missing and has been added
+    Added(String),
+    /// Remote had a different url that has been replaced
+    Updated(String),
+    /// Remote was not configured and has been removed
+    Removed(String),
+}
+
+impl std::fmt::Display for RemoteChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteChange::Added(name) => write!(f, "added remote '{}'", name),
+            RemoteChange::Updated(name) => write!(f, "updated url of remote '{}'", name),
+            RemoteChange::Removed(name) => write!(f, "removed remote '{}'", name),
+        }
+    }
+}
+
+/// Check if libgit2 failed in a way the git cli may not
+///
+/// This is the case when libgit2 cannot authenticate with the remote, fails to verify its
+/// certificate or does not support its protocol, as git has its own ssh and credential setup.
+pub fn should_fall_back(error: &Error) -> bool {
+    error.chain().any(|e| match e {
+        Error::Git(e) => {
+            matches!(
+                e.code(),
+                git2::ErrorCode::Auth | git2::ErrorCode::Certificate
+            ) || e.class() == git2::ErrorClass::Ssh
+                || e.message().contains("unsupported URL protocol")
+        }
+        _ => false,
+    })
+}
+
+/// Check if libgit2 can abort a stalled connection to a remote url
+///
+/// libgit2 talks to ssh remotes through libssh2, which blocks on the handshake, authentication
+/// and transfers without a timeout and without calling back into repo.
+pub fn can_time_out(url: &url::Url) -> bool {
+    !matches!(url.scheme(), "ssh" | "git+ssh" | "ssh+git")
+}
+
+/// Clone a repository from its remotes
+///
+/// A failed clone removes the repository again so that it is not mistaken for a clone later.
+pub fn clone<P>(path: P, remotes: &[Remote], options: &CloneOptions, use_cli: bool) -> Result<()>
 where
     P: AsRef<Path>,
 {
+    let path = path.as_ref();
+    let existed = path.exists();
+
+    let result = clone_into(path, remotes, options, use_cli);
+    if result.is_err() && !existed && path.exists() {
+        if let Err(e) = std::fs::remove_dir_all(path) {
+            warn!("failed to remove partial clone '{}': {}", path.display(), e);
+        }
+    }
+
+    result
+}
+
+fn clone_into(
+    path: &Path,
+    remotes: &[Remote],
+    options: &CloneOptions,
+    use_cli: bool,
+) -> Result<()> {
+    let remote = remotes.first().map(|r| r.name.as_str()).ok_or_else(|| {
+        Error::InvalidValue("failed to clone as no remotes are defined".to_owned())
+    })?;
+
+    // NOTE: libgit2 does not support partial clones and sparse checkouts
+    let partial = options.filter.is_some() || !options.sparse.is_empty();
+    if partial && !use_cli {
+        warn!(
+            "libgit2 does not support partial clones or sparse checkouts, using git cli for: {}",
+            path.display()
+        );
+    }
+    let use_cli = use_cli || partial;
+
     if use_cli {
-        cli::init(&path, remotes)?;
-        cli::fetch(&path)?;
-        cli::merge(&path, branch)
+        cli::init(path, remotes)?;
+        add_alternates(path, &options.references)?;
+
+        if let Some(filter) = &options.filter {
+            cli::set_partial_clone(path, remotes, filter)?;
+        }
+
+        if !options.sparse.is_empty() {
+            cli::sparse_checkout(path, &options.sparse)?;
+        }
+
+        let args: Vec<String> = options
+            .depth
+            .map(|depth| vec!["--depth".to_owned(), depth.to_string()])
+            .unwrap_or_default();
+        network::retry("clone", || cli::fetch_with(path, &args))?;
+
+        let branch = match &options.branch {
+            Some(branch) => branch.to_owned(),
+            None => network::retry("clone", || cli::default_branch(path, remote))?,
+        };
+
+        cli::checkout(path, remote, &branch)
+    } else {
+        libgit::init(path, remotes)?;
+        add_alternates(path, &options.references)?;
+        for (name, mirror) in &options.references {
+            libgit::seed_from_mirror(path, name, mirror)?;
+        }
+        let depth = options.depth.map_or(0, |d| d as i32);
+        network::retry("clone", || libgit::fetch_with(path, depth, false))?;
+
+        let branch = match &options.branch {
+            Some(branch) => branch.to_owned(),
+            None => network::retry("clone", || libgit::default_branch(path, remote))?,
+        };
+
+        libgit::checkout(path, remote, &branch)
+    }
+}
+
+/// Make the git remotes of a repository match `remotes`
+///
+/// Missing remotes are added and remotes with a different url are updated. Remotes that are not
+/// in `remotes` are only removed with `prune`.
+pub fn sync_remotes<P>(
+    path: P,
+    remotes: &[Remote],
+    prune: bool,
+    use_cli: bool,
+) -> Result<Vec<RemoteChange>>
+where
+    P: AsRef<Path>,
+{
+    let existing = if use_cli {
+        cli::remotes(&path)?
     } else {
-        libgit::clone(&path, remotes)
+        libgit::remotes(&path)?
+    };
+
+    let mut changes = Vec::new();
+    for remote in remotes {
+        let url = remote.url.as_str();
+        match existing.iter().find(|(name, _)| name == &remote.name) {
+            None if use_cli => cli::add_remote(&path, &remote.name, url)?,
+            None => libgit::add_remote(&path, &remote.name, url)?,
+            Some((_, current)) if same_url(current, url) => continue,
+            Some(_) if use_cli => cli::set_remote_url(&path, &remote.name, url)?,
+            Some(_) => libgit::set_remote_url(&path, &remote.name, url)?,
+        }
+
+        changes.push(if existing.iter().any(|(name, _)| name == &remote.name) {
+            RemoteChange::Updated(remote.name.clone())
+        } else {
+            RemoteChange::Added(remote.name.clone())
+        });
+    }
+
+    if prune {
+        for (name, _) in &existing {
+            if remotes.iter().any(|remote| &remote.name == name) {
+                continue;
+            }
+
+            if use_cli {
+                cli::remove_remote(&path, name)?;
+            } else {
+                libgit::remove_remote(&path, name)?;
+            }
+
+            changes.push(RemoteChange::Removed(name.clone()));
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Compare remote urls ignoring the scp form, a trailing slash and the `.git` suffix
+fn same_url(a: &str, b: &str) -> bool {
+    fn normalize(url: &str) -> String {
+        let url = url::Url::parse(url)
+            .or_else(|_| ScpPath::parse(url).map(|scp| scp.to_url()))
+            .map(String::from)
+            .unwrap_or_else(|_| url.to_owned());
+
+        url.trim_end_matches('/')
+            .trim_end_matches(".git")
+            .to_owned()
+    }
+
+    normalize(a) == normalize(b)
+}
+
+/// Create or update a bare mirror of a remote url
+pub fn mirror<P>(path: P, url: &str, use_cli: bool) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    network::retry("mirror", || {
+        if use_cli {
+            cli::mirror(&path, url)
+        } else {
+            libgit::mirror(&path, url)
+        }
+    })
+}
+
+fn add_alternates<P>(path: P, references: &[(String, PathBuf)]) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    if references.is_empty() {
+        return Ok(());
     }
+
+    let file = path.as_ref().join(".git/objects/info/alternates");
+    std::fs::create_dir_all(file.parent().unwrap())?;
+
+    let mut content = String::new();
+    for (_, mirror) in references {
+        content.push_str(&format!("{}\n", mirror.join("objects").display()));
+    }
+
+    std::fs::write(&file, content)
+        .context(format!("failed to write alternates: {}", file.display()))
+}
+
+/// Recursively search a folder for git repositories
+///
+/// A folder that contains a `.git` entry is a repository and is not searched any further.
+/// Symbolic links are not followed. Folders below `dir` that cannot be read are skipped with a
+/// warning.
+pub fn find_repositories<P>(dir: P) -> Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    fn find(dir: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+        if dir.join(".git").exists() {
+            debug!("Found git repository: {:#?}", dir);
+            found.push(dir.to_path_buf());
+            return Ok(());
+        }
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = match entry.and_then(|e| Ok((e.file_type()?, e.path()))) {
+                Ok((file_type, path)) if file_type.is_dir() => path,
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!("Skipping entry in {}: {}", dir.display(), e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = find(&path, found) {
+                warn!("Skipping {}: {:#}", path.display(), e);
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut found = Vec::new();
+    find(dir.as_ref(), &mut found)
+        .context(format!("failed to search: {}", dir.as_ref().display()))?;
+    found.sort();
+    Ok(found)
 }
 
+/// Mirrors a clone borrows objects from through its git alternates
+pub fn alternates<P>(path: P) -> Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let objects = path.as_ref().join(".git/objects");
+    let file = objects.join("info/alternates");
+    if !file.is_file() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(&file)
+        .context(format!("failed to read alternates: {}", file.display()))?;
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let alternate = objects.join(line);
+            match alternate.parent() {
+                Some(mirror) if alternate.ends_with("objects") => mirror.to_path_buf(),
+                _ => alternate,
+            }
+        })
+        .collect())
+}
+
+/// Stop borrowing objects from mirrors so that they can be removed
+///
+/// The borrowed objects are copied into the clone before the mirrors are removed from its
+/// alternates.
+pub fn detach_alternates<P>(path: P, mirrors: &[PathBuf]) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let remaining: Vec<PathBuf> = alternates(path)?
+        .into_iter()
+        .filter(|mirror| !mirrors.contains(mirror))
+        .collect();
+
+    // NOTE: libgit2 cannot repack a repository
+    cli::repack(path)?;
+
+    let file = path.join(".git/objects/info/alternates");
+    if remaining.is_empty() {
+        return std::fs::remove_file(&file)
+            .context(format!("failed to remove alternates: {}", file.display()));
+    }
+
+    let mut content = String::new();
+    for mirror in remaining {
+        content.push_str(&format!("{}\n", mirror.join("objects").display()));
+    }
+
+    std::fs::write(&file, content)
+        .context(format!("failed to write alternates: {}", file.display()))
+}
+
+/// Fetch all remotes, retrying network errors
 pub fn fetch<P>(path: P, use_cli: bool) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    network::retry("fetch", || {
+        if use_cli {
+            cli::fetch(&path)
+        } else {
+            libgit::fetch(&path)
+        }
+    })
+}
+
+/// Fetch the complete history if the repository is shallow
+pub fn unshallow<P>(path: P, use_cli: bool) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    network::retry("unshallow", || {
+        if use_cli {
+            cli::unshallow(&path)
+        } else {
+            libgit::unshallow(&path)
+        }
+    })
+}
+
+/// Initialize and update the submodules of a repository recursively
+///
+/// Returns the outcome for each submodule by its path.
+pub fn update_submodules<P>(path: P, use_cli: bool) -> Result<Vec<(String, Result<()>)>>
 where
     P: AsRef<Path>,
 {
     if use_cli {
-        cli::fetch(&path)
+        Ok(cli::submodules(&path)?
+            .into_iter()
+            .map(|submodule| {
+                let result = network::retry("submodule update", || {
+                    cli::update_submodule(&path, &submodule)
+                });
+                (submodule, result)
+            })
+            .collect())
     } else {
-        libgit::fetch(&path)
+        libgit::update_submodules(&path)
     }
 }
 
-pub fn merge<P>(path: P, use_cli: bool) -> Result<()>
+/// Download git lfs objects. libgit2 does not support lfs so the git cli is always used
+pub fn lfs_pull<P>(path: P) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    network::retry("lfs pull", || cli::lfs_pull(&path))
+}
+
+/// Fetch all remotes and bring the checked out branch up to date with its upstream
+///
+/// Branches without an upstream and detached HEADs are only fetched. With `all_branches` the
+/// other local branches are fast-forwarded and the branches whose upstream is gone are reported.
+pub fn update<P>(path: P, options: &PullOptions, use_cli: bool) -> Result<BranchReport>
+where
+    P: AsRef<Path>,
+{
+    match (options.all_branches, use_cli) {
+        (false, _) => fetch(&path, use_cli)?,
+        (true, true) => network::retry("fetch", || cli::fetch_prune(&path))?,
+        (true, false) => network::retry("fetch", || libgit::fetch_prune(&path))?,
+    }
+
+    // NOTE: Fetching and resetting do not need the local changes to be stashed, the first keeps
+    // them and the second discards them
+    let stash = options.autostash
+        && matches!(
+            options.strategy,
+            UpdateStrategy::FfOnly | UpdateStrategy::Rebase
+        )
+        && status(&path, use_cli)?.changed > 0;
+
+    if stash {
+        stash_save(&path, use_cli)?;
+    }
+
+    let result = match (options.strategy, use_cli) {
+        (UpdateStrategy::Fetch, _) => Ok(()),
+        (UpdateStrategy::FfOnly, true) => cli::ff_merge(&path),
+        (UpdateStrategy::FfOnly, false) => libgit::ff_merge(&path),
+        (UpdateStrategy::Rebase, true) => cli::rebase(&path),
+        (UpdateStrategy::Rebase, false) => libgit::rebase(&path),
+        (UpdateStrategy::ResetHard, true) => cli::reset_hard(&path),
+        (UpdateStrategy::ResetHard, false) => libgit::reset_hard(&path),
+    };
+
+    if stash {
+        stash_pop(&path, use_cli)?;
+    }
+
+    result?;
+
+    if !options.all_branches {
+        return Ok(BranchReport::default());
+    }
+
+    Ok(BranchReport {
+        updated: fast_forward_branches(&path, use_cli)?,
+        gone: gone_branches(&path, use_cli)?,
+    })
+}
+
+/// Stash the local changes of the working tree and index
+pub fn stash_save<P>(path: P, use_cli: bool) -> Result<()>
 where
     P: AsRef<Path>,
 {
     if use_cli {
-        cli::fetch(&path)?;
-        cli::ff_merge(&path)
+        cli::stash_save(&path)
     } else {
-        libgit::fetch(&path)?;
-        libgit::ff_merge(&path)
+        libgit::stash_save(&path)
     }
 }
 
+/// Restore the most recently stashed changes and drop them from the stash
+pub fn stash_pop<P>(path: P, use_cli: bool) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let result = if use_cli {
+        cli::stash_pop(&path)
+    } else {
+        libgit::stash_pop(&path)
+    };
+
+    result.context("failed to restore stashed changes, they are kept in 'git stash'")
+}
+
+/// Fast-forward every local branch, except the checked out one, whose upstream has moved
+pub fn fast_forward_branches<P>(path: P, use_cli: bool) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    if use_cli {
+        cli::fast_forward_branches(&path)
+    } else {
+        libgit::fast_forward_branches(&path)
+    }
+}
+
+/// Local branches whose upstream no longer exists on the remote
+pub fn gone_branches<P>(path: P, use_cli: bool) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    if use_cli {
+        cli::gone_branches(&path)
+    } else {
+        libgit::gone_branches(&path)
+    }
+}
+
+/// Check if every commit of a local branch is reachable from the checked out branch
+pub fn is_merged<P>(path: P, branch: &str, use_cli: bool) -> Result<bool>
+where
+    P: AsRef<Path>,
+{
+    if use_cli {
+        cli::is_merged(&path, branch)
+    } else {
+        libgit::is_merged(&path, branch)
+    }
+}
+
+/// Delete a local branch, failing if it is not merged into the checked out branch
+pub fn delete_branch<P>(path: P, branch: &str, use_cli: bool) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    if use_cli {
+        cli::delete_branch(&path, branch)
+    } else {
+        libgit::delete_branch(&path, branch)
+    }
+}
+
+pub fn status<P>(path: P, use_cli: bool) -> Result<Status>
+where
+    P: AsRef<Path>,
+{
+    if use_cli {
+        cli::status(&path)
+    } else {
+        libgit::status(&path)
+    }
+}
+
+mod backend;
 pub mod cli;
+pub(crate) mod credentials;
 pub mod libgit;
+pub(crate) mod network;
+mod strategy;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::Query;
+    use git2::{Repository, Signature};
+    use std::{
+        path::PathBuf,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// Temporary directory that is removed when dropped
+    pub(crate) struct TempDir(pub(crate) PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new() -> Self {
+            let id = COUNTER.fetch_add(1, Ordering::SeqCst);
+            let path = std::env::temp_dir().join(format!("repo-git-{}-{}", std::process::id(), id));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Commit a file directly into a bare repository on the branch HEAD points to
+    ///
+    /// The file can be nested in one folder like `dir/file`.
+    pub(crate) fn commit(repo: &Repository, file: &str, content: &str) {
+        let sig = Signature::now("repo", "repo@example.com").unwrap();
+        let mut oid = repo.blob(content.as_bytes()).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parent_tree = parent.as_ref().map(|c| c.tree().unwrap());
+
+        let (file, mode) = match file.split_once('/') {
+            Some((dir, name)) => {
+                let subtree = parent_tree
+                    .as_ref()
+                    .and_then(|t| t.get_name(dir))
+                    .map(|e| repo.find_tree(e.id()).unwrap());
+                let mut builder = repo.treebuilder(subtree.as_ref()).unwrap();
+                builder.insert(name, oid, 0o100644).unwrap();
+                oid = builder.write().unwrap();
+                (dir, 0o040000)
+            }
+            None => (file, 0o100644),
+        };
+
+        let mut builder = repo.treebuilder(parent_tree.as_ref()).unwrap();
+        builder.insert(file, oid, mode).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, file, &tree, &parents)
+            .unwrap();
+    }
+
+    /// Commit a submodule entry into a bare repository, the `.gitmodules` file is not changed
+    fn commit_gitlink(repo: &Repository, name: &str, oid: git2::Oid) {
+        let sig = Signature::now("repo", "repo@example.com").unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let mut builder = repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+        builder.insert(name, oid, 0o160000).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &[&parent])
+            .unwrap();
+    }
+
+    pub(crate) fn bare_repository(dir: &TempDir) -> (Repository, PathBuf) {
+        bare_repository_named(dir, "origin.git")
+    }
+
+    fn bare_repository_named(dir: &TempDir, name: &str) -> (Repository, PathBuf) {
+        let path = dir.0.join(name);
+        let repo = Repository::init_bare(&path).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        commit(&repo, "readme", "hello");
+        (repo, path)
+    }
+
+    fn clone_and_update(query: &str, origin: &Repository, dir: &TempDir, use_cli: bool) {
+        let url = match Query::parse(query).unwrap() {
+            Query::Url(url) => url,
+            query => panic!("expected a file url, found: {:?}", query),
+        };
+
+        let path = dir.0.join("workspace").join("clone");
+        let options = CloneOptions::default();
+        clone(&path, &[Remote::new(url)], &options, use_cli).unwrap();
+        assert!(path.join("readme").is_file());
+
+        let status = status(&path, use_cli).unwrap();
+        assert_eq!(status.branch.as_deref(), Some("main"));
+
+        commit(origin, "license", "apache");
+        let options = PullOptions {
+            strategy: UpdateStrategy::FfOnly,
+            ..Default::default()
+        };
+        update(&path, &options, use_cli).unwrap();
+        assert!(path.join("license").is_file());
+    }
+
+    fn clone_with_mirror(use_cli: bool) {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        let url = url::Url::from_file_path(&path).unwrap();
+
+        let reference = dir.0.join("mirror").join("origin.git");
+        mirror(&reference, url.as_str(), use_cli).unwrap();
+
+        commit(&origin, "license", "apache");
+        mirror(&reference, url.as_str(), use_cli).unwrap();
+
+        let clone_path = dir.0.join("workspace").join("clone");
+        let options = CloneOptions {
+            references: vec![("origin".to_owned(), reference.clone())],
+            ..Default::default()
+        };
+        clone(&clone_path, &[Remote::new(url)], &options, use_cli).unwrap();
+
+        let alternates =
+            std::fs::read_to_string(clone_path.join(".git/objects/info/alternates")).unwrap();
+        assert_eq!(
+            alternates.trim(),
+            reference.join("objects").to_str().unwrap()
+        );
+        assert!(clone_path.join("license").is_file());
+        assert_eq!(
+            status(&clone_path, use_cli).unwrap().upstream.as_deref(),
+            Some("origin/main")
+        );
+    }
+
+    #[test]
+    fn detach_from_mirror() {
+        let dir = TempDir::new();
+        let (_, path) = bare_repository(&dir);
+        let url = url::Url::from_file_path(&path).unwrap();
+
+        let reference = dir.0.join("mirror").join("origin.git");
+        mirror(&reference, url.as_str(), false).unwrap();
+
+        let clone_path = dir.0.join("workspace").join("clone");
+        let options = CloneOptions {
+            references: vec![("origin".to_owned(), reference.clone())],
+            ..Default::default()
+        };
+        clone(&clone_path, &[Remote::new(url)], &options, false).unwrap();
+        let mirrors = vec![reference.clone()];
+        assert_eq!(alternates(&clone_path).unwrap(), mirrors);
+
+        detach_alternates(&clone_path, &mirrors).unwrap();
+        std::fs::remove_dir_all(&reference).unwrap();
+        assert!(alternates(&clone_path).unwrap().is_empty());
+
+        let repo = Repository::open(&clone_path).unwrap();
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        let entry = tree.get_name("readme").unwrap();
+        assert!(repo.find_blob(entry.id()).is_ok());
+    }
+
+    #[test]
+    fn clone_with_mirror_libgit() {
+        clone_with_mirror(false);
+    }
+
+    #[test]
+    fn clone_with_mirror_cli() {
+        clone_with_mirror(true);
+    }
+
+    #[test]
+    fn clone_partial_cli() {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        origin
+            .config()
+            .unwrap()
+            .set_bool("uploadpack.allowFilter", true)
+            .unwrap();
+        commit(&origin, "docs/guide", "guide");
+        commit(&origin, "src/main", "main");
+
+        let url = url::Url::from_file_path(&path).unwrap();
+        let clone_path = dir.0.join("workspace").join("clone");
+        let options = CloneOptions {
+            depth: Some(1),
+            filter: Some("blob:none".to_owned()),
+            sparse: vec!["src".to_owned()],
+            ..Default::default()
+        };
+        clone(&clone_path, &[Remote::new(url)], &options, false).unwrap();
+
+        assert!(clone_path.join("src/main").is_file());
+        assert!(!clone_path.join("docs").exists());
+
+        let clone = Repository::open(&clone_path).unwrap();
+        assert!(clone.is_shallow());
+
+        let config = clone.config().unwrap().snapshot().unwrap();
+        assert!(config.get_bool("remote.origin.promisor").unwrap());
+        assert_eq!(
+            config.get_str("remote.origin.partialclonefilter").unwrap(),
+            "blob:none"
+        );
+
+        unshallow(&clone_path, true).unwrap();
+        let clone = Repository::open(&clone_path).unwrap();
+        assert!(!clone.is_shallow());
+    }
+
+    fn submodules(use_cli: bool) {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        let (lib, lib_path) = bare_repository_named(&dir, "lib.git");
+        let lib_url = url::Url::from_file_path(&lib_path).unwrap();
+        let missing_url = url::Url::from_file_path(dir.0.join("missing.git")).unwrap();
+
+        let gitmodules = format!(
+            "[submodule \"lib\"]\n\tpath = lib\n\turl = {}\n\
+             [submodule \"missing\"]\n\tpath = missing\n\turl = {}\n",
+            lib_url, missing_url
+        );
+        commit(&origin, ".gitmodules", &gitmodules);
+
+        let lib_head = lib.head().unwrap().target().unwrap();
+        commit_gitlink(&origin, "lib", lib_head);
+        commit_gitlink(&origin, "missing", lib_head);
+
+        let url = url::Url::from_file_path(&path).unwrap();
+        let clone_path = dir.0.join("workspace").join("clone");
+        clone(
+            &clone_path,
+            &[Remote::new(url)],
+            &CloneOptions::default(),
+            use_cli,
+        )
+        .unwrap();
+
+        // NOTE: The git cli refuses submodules on the local filesystem unless allowed
+        let mut config = Repository::open(&clone_path).unwrap().config().unwrap();
+        config.set_str("protocol.file.allow", "always").unwrap();
+
+        let mut results = update_submodules(&clone_path, use_cli).unwrap();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "lib");
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].0, "missing");
+        assert!(results[1].1.is_err());
+        assert!(clone_path.join("lib/readme").is_file());
+    }
+
+    #[test]
+    fn submodules_libgit() {
+        submodules(false);
+    }
+
+    #[test]
+    fn submodules_cli() {
+        submodules(true);
+    }
+
+    #[test]
+    fn clone_file_url_libgit() {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        let url = url::Url::from_file_path(&path).unwrap();
+        clone_and_update(url.as_str(), &origin, &dir, false);
+    }
+
+    #[test]
+    fn clone_file_url_cli() {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        let url = url::Url::from_file_path(&path).unwrap();
+        clone_and_update(url.as_str(), &origin, &dir, true);
+    }
+
+    #[test]
+    fn clone_local_path_libgit() {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        clone_and_update(path.to_str().unwrap(), &origin, &dir, false);
+    }
+
+    #[test]
+    fn clone_local_path_cli() {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        clone_and_update(path.to_str().unwrap(), &origin, &dir, true);
+    }
+
+    /// Commit a file in the working tree of a non-bare repository
+    fn commit_workdir(path: &Path, file: &str, content: &str) {
+        let repo = Repository::open(path).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "repo").unwrap();
+        config.set_str("user.email", "repo@example.com").unwrap();
+
+        std::fs::write(path.join(file), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+
+        let sig = Signature::now("repo", "repo@example.com").unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, file, &tree, &[&parent])
+            .unwrap();
+    }
+
+    /// Clone a repository and diverge it from its origin with one commit on each side
+    fn diverged_clone(dir: &TempDir, use_cli: bool) -> PathBuf {
+        let (origin, path) = bare_repository(dir);
+        let url = url::Url::from_file_path(&path).unwrap();
+
+        let clone_path = dir.0.join("workspace").join("clone");
+        let options = CloneOptions::default();
+        clone(&clone_path, &[Remote::new(url)], &options, use_cli).unwrap();
+
+        commit_workdir(&clone_path, "local", "mine");
+        commit(&origin, "license", "apache");
+        clone_path
+    }
+
+    fn pull(
+        path: &Path,
+        strategy: UpdateStrategy,
+        autostash: bool,
+        use_cli: bool,
+    ) -> Result<BranchReport> {
+        let options = PullOptions {
+            strategy,
+            autostash,
+            ..Default::default()
+        };
+        update(path, &options, use_cli)
+    }
+
+    fn update_strategy(use_cli: bool) {
+        let dir = TempDir::new();
+        let path = diverged_clone(&dir, use_cli);
+        pull(&path, UpdateStrategy::Fetch, false, use_cli).unwrap();
+        assert!(!path.join("license").is_file());
+        assert!(pull(&path, UpdateStrategy::FfOnly, false, use_cli).is_err());
+
+        std::fs::write(path.join("readme"), "changed").unwrap();
+        assert!(pull(&path, UpdateStrategy::Rebase, false, use_cli).is_err());
+        pull(&path, UpdateStrategy::Rebase, true, use_cli).unwrap();
+        assert!(path.join("license").is_file());
+        assert!(path.join("local").is_file());
+        assert_eq!(
+            std::fs::read_to_string(path.join("readme")).unwrap(),
+            "changed"
+        );
+        let status = status(&path, use_cli).unwrap();
+        assert_eq!((status.ahead, status.behind), (1, 0));
+
+        pull(&path, UpdateStrategy::ResetHard, false, use_cli).unwrap();
+        assert!(!path.join("local").is_file());
+        assert_eq!(
+            std::fs::read_to_string(path.join("readme")).unwrap(),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn update_strategy_libgit() {
+        update_strategy(false);
+    }
+
+    #[test]
+    fn update_strategy_cli() {
+        update_strategy(true);
+    }
+
+    fn update_all_branches(use_cli: bool) {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        let head = origin.head().unwrap().peel_to_commit().unwrap();
+        origin.branch("stale", &head, false).unwrap();
+        let url = url::Url::from_file_path(&path).unwrap();
+
+        let clone_path = dir.0.join("workspace").join("clone");
+        let options = CloneOptions::default();
+        clone(&clone_path, &[Remote::new(url)], &options, use_cli).unwrap();
+
+        let repo = Repository::open(&clone_path).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        for (name, upstream) in [
+            ("feature", "origin/main"),
+            ("stale", "origin/stale"),
+            ("wip", "origin/stale"),
+        ] {
+            let mut branch = repo.branch(name, &head, false).unwrap();
+            branch.set_upstream(Some(upstream)).unwrap();
+        }
+
+        let sig = Signature::now("repo", "repo@example.com").unwrap();
+        repo.commit(
+            Some("refs/heads/wip"),
+            &sig,
+            &sig,
+            "wip",
+            &head.tree().unwrap(),
+            &[&head],
+        )
+        .unwrap();
+
+        commit(&origin, "license", "apache");
+        origin
+            .find_branch("stale", git2::BranchType::Local)
+            .unwrap()
+            .delete()
+            .unwrap();
+
+        let options = PullOptions {
+            all_branches: true,
+            ..Default::default()
+        };
+        let report = update(&clone_path, &options, use_cli).unwrap();
+        assert_eq!(report.updated, vec!["feature".to_owned()]);
+        assert_eq!(report.gone, vec!["stale".to_owned(), "wip".to_owned()]);
+        assert!(clone_path.join("license").is_file());
+
+        assert!(is_merged(&clone_path, "stale", use_cli).unwrap());
+        assert!(!is_merged(&clone_path, "wip", use_cli).unwrap());
+
+        delete_branch(&clone_path, "stale", use_cli).unwrap();
+        assert!(delete_branch(&clone_path, "wip", use_cli).is_err());
+        assert_eq!(
+            gone_branches(&clone_path, use_cli).unwrap(),
+            vec!["wip".to_owned()]
+        );
+    }
+
+    #[test]
+    fn update_all_branches_libgit() {
+        update_all_branches(false);
+    }
+
+    #[test]
+    fn update_all_branches_cli() {
+        update_all_branches(true);
+    }
+
+    #[test]
+    fn update_blocker() {
+        let status = Status {
+            branch: Some("main".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(status.update_blocker(UpdateStrategy::FfOnly, false), None);
+
+        let dirty = Status {
+            changed: 2,
+            ..status.clone()
+        };
+        assert!(dirty
+            .update_blocker(UpdateStrategy::Rebase, false)
+            .is_some());
+        assert_eq!(dirty.update_blocker(UpdateStrategy::Rebase, true), None);
+        assert_eq!(dirty.update_blocker(UpdateStrategy::ResetHard, false), None);
+
+        let detached = Status::default();
+        assert_eq!(
+            detached.update_blocker(UpdateStrategy::FfOnly, true),
+            Some("detached HEAD".to_owned())
+        );
+        assert_eq!(detached.update_blocker(UpdateStrategy::Fetch, false), None);
+
+        let merging = Status {
+            operation: Some("merge".to_owned()),
+            ..status
+        };
+        assert_eq!(
+            merging.update_blocker(UpdateStrategy::ResetHard, true),
+            Some("merge in progress".to_owned())
+        );
+    }
+
+    fn operation_in_progress(use_cli: bool) {
+        let dir = TempDir::new();
+        let path = diverged_clone(&dir, use_cli);
+        assert_eq!(status(&path, use_cli).unwrap().operation, None);
+
+        // A conflicting change makes the rebase stop half way
+        fetch(&path, use_cli).unwrap();
+        commit_workdir(&path, "license", "mit");
+        let output = std::process::Command::new("git")
+            .args(["rebase", "origin/main"])
+            .current_dir(&path)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert_eq!(
+            status(&path, use_cli).unwrap().operation.as_deref(),
+            Some("rebase")
+        );
+    }
+
+    #[test]
+    fn operation_in_progress_libgit() {
+        operation_in_progress(false);
+    }
+
+    #[test]
+    fn operation_in_progress_cli() {
+        operation_in_progress(true);
+    }
+
+    fn autostash_ff_only(use_cli: bool) {
+        let dir = TempDir::new();
+        let (origin, path) = bare_repository(&dir);
+        let url = url::Url::from_file_path(&path).unwrap();
+        let clone_path = dir.0.join("workspace").join("clone");
+        clone(
+            &clone_path,
+            &[Remote::new(url)],
+            &CloneOptions::default(),
+            use_cli,
+        )
+        .unwrap();
+
+        commit(&origin, "license", "apache");
+        std::fs::write(clone_path.join("readme"), "changed").unwrap();
+        pull(&clone_path, UpdateStrategy::FfOnly, true, use_cli).unwrap();
+
+        assert!(clone_path.join("license").is_file());
+        assert_eq!(
+            std::fs::read_to_string(clone_path.join("readme")).unwrap(),
+            "changed"
+        );
+    }
+
+    #[test]
+    fn autostash_ff_only_libgit() {
+        autostash_ff_only(false);
+    }
+
+    #[test]
+    fn autostash_ff_only_cli() {
+        autostash_ff_only(true);
+    }
+
+    fn clone_all_remotes(use_cli: bool) {
+        let dir = TempDir::new();
+        let (_, origin) = bare_repository(&dir);
+        let (fork, fork_path) = bare_repository_named(&dir, "fork.git");
+        fork.set_head("refs/heads/feature").unwrap();
+        commit(&fork, "license", "apache");
+
+        let remotes = [
+            Remote::with_name("upstream", url::Url::from_file_path(&origin).unwrap()),
+            Remote::with_name("fork", url::Url::from_file_path(&fork_path).unwrap()),
+        ];
+        let path = dir.0.join("workspace").join("clone");
+        clone(&path, &remotes, &CloneOptions::default(), use_cli).unwrap();
+
+        let repo = Repository::open(&path).unwrap();
+        assert!(repo.find_remote("origin").is_err());
+        assert!(repo
+            .find_branch("upstream/main", git2::BranchType::Remote)
+            .is_ok());
+        assert!(repo
+            .find_branch("fork/feature", git2::BranchType::Remote)
+            .is_ok());
+    }
+
+    #[test]
+    fn clone_all_remotes_libgit() {
+        clone_all_remotes(false);
+    }
+
+    #[test]
+    fn clone_all_remotes_cli() {
+        clone_all_remotes(true);
+    }
+
+    #[test]
+    fn fall_back_to_cli() {
+        let dir = TempDir::new();
+        let missing = url::Url::from_file_path(dir.0.join("missing.git")).unwrap();
+        let path = dir.0.join("missing");
+        let error = clone(
+            &path,
+            &[Remote::new(missing)],
+            &CloneOptions::default(),
+            false,
+        );
+        assert!(!should_fall_back(&error.unwrap_err()));
+        assert!(!path.exists(), "a failed clone is removed");
+
+        let auth = git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Callback,
+            "no credentials",
+        );
+        assert!(should_fall_back(
+            &Error::Git(auth).context("failed to fetch")
+        ));
+
+        let protocol = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "unsupported URL protocol",
+        );
+        assert!(should_fall_back(&Error::Git(protocol)));
+    }
+
+    #[test]
+    fn ssh_remotes_cannot_time_out() {
+        let url = |s| url::Url::parse(s).unwrap();
+        assert!(!can_time_out(&url("ssh://git@github.com/user/repo.git")));
+        assert!(!can_time_out(
+            &ScpPath::parse("git@github.com:user/repo").unwrap().to_url()
+        ));
+        assert!(can_time_out(&url("https://github.com/user/repo.git")));
+        assert!(can_time_out(&url("git://github.com/user/repo.git")));
+    }
+
+    #[test]
+    fn same_url_forms() {
+        assert!(same_url(
+            "git@github.com:user/repo.git",
+            "ssh://git@github.com/user/repo"
+        ));
+        assert!(same_url(
+            "https://github.com/user/repo.git",
+            "https://github.com/user/repo/"
+        ));
+        assert!(!same_url(
+            "https://github.com/user/repo",
+            "https://github.com/fork/repo"
+        ));
+    }
+
+    fn sync_remotes_with(use_cli: bool) {
+        let dir = TempDir::new();
+        let (_, origin) = bare_repository(&dir);
+        let (_, fork) = bare_repository_named(&dir, "fork.git");
+        let origin = url::Url::from_file_path(&origin).unwrap();
+        let fork = url::Url::from_file_path(&fork).unwrap();
+
+        let path = dir.0.join("workspace").join("clone");
+        let remotes = [
+            Remote::new(origin.clone()),
+            Remote::with_name("extra", origin.clone()),
+        ];
+        clone(&path, &remotes, &CloneOptions::default(), use_cli).unwrap();
+
+        let remotes = [
+            Remote::new(fork.clone()),
+            Remote::with_name("upstream", origin.clone()),
+        ];
+        let changes = sync_remotes(&path, &remotes, false, use_cli).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                RemoteChange::Updated("origin".to_owned()),
+                RemoteChange::Added("upstream".to_owned()),
+            ]
+        );
+
+        let changes = sync_remotes(&path, &remotes, true, use_cli).unwrap();
+        assert_eq!(changes, vec![RemoteChange::Removed("extra".to_owned())]);
+
+        let mut current = libgit::remotes(&path).unwrap();
+        current.sort();
+        assert_eq!(
+            current,
+            vec![
+                ("origin".to_owned(), fork.to_string()),
+                ("upstream".to_owned(), origin.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn sync_remotes_libgit() {
+        sync_remotes_with(false);
+    }
+
+    #[test]
+    fn sync_remotes_cli() {
+        sync_remotes_with(true);
+    }
+
+    #[test]
+    fn find_nested_repositories() {
+        let dir = TempDir::new();
+        let root = dir.0.join("root");
+        for path in &["a", "group/b", "group/nested/c"] {
+            Repository::init(root.join(path)).unwrap();
+        }
+        // Repositories inside of a clone are part of that clone
+        Repository::init(root.join("a/vendor/d")).unwrap();
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+
+        let found = find_repositories(&root).unwrap();
+        let expected: Vec<PathBuf> = ["a", "group/b", "group/nested/c"]
+            .iter()
+            .map(|p| root.join(p))
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn find_repositories_skips_unreadable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new();
+        let root = dir.0.join("root");
+        Repository::init(root.join("a")).unwrap();
+        Repository::init(root.join("locked/b")).unwrap();
+
+        let locked = root.join("locked");
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+        let readable = std::fs::read_dir(&locked).is_ok();
+        let found = find_repositories(&root);
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        // Permissions do not apply to root, the folder is still searched then
+        let found = found.unwrap();
+        if readable {
+            assert_eq!(found, vec![root.join("a"), root.join("locked/b")]);
+        } else {
+            assert_eq!(found, vec![root.join("a")]);
+        }
+    }
+}
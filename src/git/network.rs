@@ -0,0 +1,363 @@
+//! Timeouts and retries of the git operations that talk to a remote
+//!
+//! Like credentials, the settings are tracked per thread with a [`Scope`] so that both backends
+//! can enforce them deep inside an operation. Each attempt of [`retry`] has its own deadline. The
+//! connect timeout of libgit2 is global, it is set once when the [`Workspace`](crate::Workspace)
+//! is created.
+
+use crate::{Error, Result};
+use std::{
+    cell::{Cell, RefCell},
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Network>> = const { RefCell::new(None) };
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Exit codes of fatal errors, `128` of git and `2` of git-lfs
+const FATAL_EXIT_CODES: [i32; 2] = [128, 2];
+
+/// Causes of fatal git errors that are likely to go away when trying again
+///
+/// git exits with the same code for every fatal error, so only its message tells a dropped
+/// connection apart from a missing repository.
+const TRANSIENT: [&str; 6] = [
+    "timed out",
+    "connection reset",
+    "connection refused",
+    "temporary failure in name resolution",
+    "early eof",
+    "the remote end hung up unexpectedly",
+];
+
+/// Timeouts and retries of the operations that talk to a remote
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Network {
+    /// Time to wait for a remote to connect or send data, `None` waits forever
+    pub connect_timeout: Option<Duration>,
+    /// Time a single attempt of an operation may take, `None` waits forever
+    pub timeout: Option<Duration>,
+    /// Number of times an operation is tried again after a retryable error
+    pub retries: u32,
+    /// Delay before the first retry, doubled for every following retry
+    pub retry_delay: Duration,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self {
+            connect_timeout: None,
+            timeout: None,
+            retries: 0,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Uses network settings for the git operations of the current thread until dropped
+pub(crate) struct Scope;
+
+impl Scope {
+    pub(crate) fn new(network: Network) -> Self {
+        CURRENT.with(|current| *current.borrow_mut() = Some(network));
+        Scope
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.borrow_mut().take());
+    }
+}
+
+/// Network settings of the current thread's [`Scope`], or the defaults outside of one
+pub(crate) fn current() -> Network {
+    CURRENT.with(|current| current.borrow().clone().unwrap_or_default())
+}
+
+/// Time left before the current attempt times out, `None` if it has no timeout
+pub(crate) fn remaining() -> Option<Duration> {
+    DEADLINE
+        .with(|deadline| deadline.get())
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// Check if the current attempt has run out of time
+pub(crate) fn expired() -> bool {
+    remaining().is_some_and(|remaining| remaining.is_zero())
+}
+
+/// Environment variables that make the git cli give up on stalled http transfers
+pub(crate) fn cli_env() -> Vec<(&'static str, String)> {
+    match current().connect_timeout {
+        Some(timeout) => vec![
+            ("GIT_HTTP_LOW_SPEED_LIMIT", "1".to_owned()),
+            (
+                "GIT_HTTP_LOW_SPEED_TIME",
+                timeout.as_secs().max(1).to_string(),
+            ),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// Check if an error is a network failure that may not happen again, like a timeout or a dropped
+/// connection
+///
+/// Failing to authenticate, a missing repository or a rejected request are permanent.
+pub fn is_retryable(error: &Error) -> bool {
+    error.chain().any(|e| match e {
+        Error::Timeout(_) => true,
+        Error::Git(e) => {
+            let permanent = matches!(
+                e.code(),
+                git2::ErrorCode::Auth | git2::ErrorCode::Certificate | git2::ErrorCode::NotFound
+            );
+            // NOTE: libgit2 reports a url scheme it was built without as a network error
+            let unsupported = e.message().contains("unsupported");
+
+            !permanent
+                && !unsupported
+                && matches!(
+                    e.class(),
+                    git2::ErrorClass::Net | git2::ErrorClass::Os | git2::ErrorClass::Ssh
+                )
+        }
+        Error::Io(e) => matches!(
+            e.kind(),
+            std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::BrokenPipe
+                | std::io::ErrorKind::UnexpectedEof
+        ),
+        Error::Command {
+            code: Some(code),
+            reason: Some(reason),
+            ..
+        } if FATAL_EXIT_CODES.contains(code) => {
+            let reason = reason.to_lowercase();
+            TRANSIENT.iter().any(|transient| reason.contains(transient))
+        }
+        _ => false,
+    })
+}
+
+/// Execute an operation that talks to a remote, trying it again after retryable errors
+///
+/// Every attempt has to finish within the timeout of the current [`Scope`], the delay between
+/// attempts grows exponentially.
+pub(crate) fn retry<T, F>(operation: &str, mut f: F) -> Result<T>
+where
+    F: FnMut() -> Result<T>,
+{
+    let network = current();
+    let mut attempt = 0;
+
+    loop {
+        let start = Instant::now();
+        DEADLINE.with(|deadline| deadline.set(network.timeout.map(|t| start + t)));
+        let result = f();
+        DEADLINE.with(|deadline| deadline.set(None));
+
+        let error = match result {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        // NOTE: libgit2 reports a transfer aborted by a callback as a generic error
+        let error = match network.timeout {
+            Some(timeout)
+                if start.elapsed() >= timeout
+                    && !error.chain().any(|e| matches!(e, Error::Timeout(_))) =>
+            {
+                debug!("{} aborted after timeout: {:#}", operation, error);
+                Error::Timeout(timeout)
+            }
+            _ => error,
+        };
+
+        if !is_retryable(&error) {
+            return Err(error.context(format!("{} failed with a permanent error", operation)));
+        }
+
+        if attempt >= network.retries {
+            return Err(error.context(format!(
+                "{} failed with a network error after {} attempts",
+                operation,
+                attempt + 1
+            )));
+        }
+
+        let delay = network.retry_delay * 2u32.saturating_pow(attempt);
+        warn!(
+            "{} failed with a network error, retrying in {}s: {:#}",
+            operation,
+            delay.as_secs_f32(),
+            error
+        );
+        std::thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::libgit;
+
+    /// Error of a git command that died with a fatal error
+    fn fatal(reason: &str) -> Error {
+        Error::Command {
+            command: "git fetch".to_owned(),
+            code: Some(128),
+            reason: Some(reason.to_owned()),
+        }
+    }
+
+    fn network() -> Network {
+        Network {
+            retries: 2,
+            retry_delay: Duration::from_millis(1),
+            ..Network::default()
+        }
+    }
+
+    #[test]
+    fn retry_network_errors() {
+        let _scope = Scope::new(network());
+
+        let mut attempts = 0;
+        let result: Result<()> = retry("fetch", || {
+            attempts += 1;
+            Err(fatal(
+                "fatal: unable to access: Connection timed out after 30001 milliseconds",
+            ))
+        });
+        assert_eq!(attempts, 3);
+        assert!(format!("{:#}", result.unwrap_err())
+            .starts_with("fetch failed with a network error after 3 attempts"));
+
+        let mut attempts = 0;
+        let result = retry("fetch", || {
+            attempts += 1;
+            if attempts < 2 {
+                Err(Error::Timeout(Duration::from_secs(1)))
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[test]
+    fn permanent_errors() {
+        let _scope = Scope::new(network());
+
+        let mut attempts = 0;
+        let result: Result<()> = retry("fetch", || {
+            attempts += 1;
+            Err(git2::Error::new(
+                git2::ErrorCode::Auth,
+                git2::ErrorClass::Net,
+                "authentication required",
+            )
+            .into())
+        });
+        assert_eq!(attempts, 1);
+        assert!(result.unwrap_err().to_string().contains("permanent error"));
+
+        let error = fatal("fatal: repository 'https://example.com/repo' not found");
+        assert!(!is_retryable(&error));
+    }
+
+    #[test]
+    fn transient_messages() {
+        for transient in &TRANSIENT {
+            let reason = format!("fatal: {}", transient.to_uppercase());
+            assert!(is_retryable(&fatal(&reason)), "{}", transient);
+
+            // NOTE: Only fatal errors are network failures, a failed merge can mention anything
+            let error = Error::Command {
+                command: "git merge".to_owned(),
+                code: Some(1),
+                reason: Some(reason),
+            };
+            assert!(!is_retryable(&error), "{}", transient);
+        }
+
+        for permanent in &[
+            "fatal: unable to access: Could not resolve host: example.com",
+            "fatal: unable to access: The requested URL returned error: 500",
+            "fatal: Authentication failed for 'https://example.com/repo'",
+        ] {
+            assert!(!is_retryable(&fatal(permanent)), "{}", permanent);
+        }
+
+        let lfs = Error::Command {
+            command: "git lfs pull".to_owned(),
+            code: Some(2),
+            reason: Some("batch response: connection reset by peer".to_owned()),
+        };
+        assert!(is_retryable(&lfs));
+        assert!(!is_retryable(&Error::other("connection reset")));
+    }
+
+    #[test]
+    fn structured_errors() {
+        let git = |code, class| Error::Git(git2::Error::new(code, class, "failed"));
+        assert!(is_retryable(&git(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net
+        )));
+        assert!(is_retryable(&git(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Os
+        )));
+        assert!(!is_retryable(&git(
+            git2::ErrorCode::Certificate,
+            git2::ErrorClass::Net
+        )));
+        assert!(!is_retryable(&git(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Reference
+        )));
+
+        let io = |kind| Error::Io(std::io::Error::from(kind));
+        assert!(is_retryable(&io(std::io::ErrorKind::ConnectionReset)));
+        assert!(!is_retryable(&io(std::io::ErrorKind::NotFound)));
+
+        let timeout = Error::Timeout(Duration::from_secs(1)).context("fetch failed");
+        assert!(is_retryable(&timeout));
+    }
+
+    #[test]
+    fn timeout_attempts() {
+        let _scope = Scope::new(Network {
+            timeout: Some(Duration::from_millis(50)),
+            ..network()
+        });
+
+        let result: Result<()> = retry("fetch", || {
+            while !expired() {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            Err(Error::other("callback aborted"))
+        });
+        let error = result.unwrap_err();
+        assert!(matches!(error.root_cause(), Error::Timeout(_)));
+        assert_eq!(remaining(), None);
+    }
+
+    #[test]
+    fn server_timeouts() {
+        assert_eq!(libgit::timeout_millis(Duration::from_secs(7)), 7000);
+        assert_eq!(
+            libgit::timeout_millis(Duration::MAX),
+            std::os::raw::c_int::MAX
+        );
+    }
+}
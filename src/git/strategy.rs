@@ -0,0 +1,48 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// How a cloned repository is brought up to date with its upstream
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdateStrategy {
+    /// Only fetch the remotes, the working tree is not changed
+    Fetch,
+    /// Fast-forward the checked out branch, failing if it has diverged from its upstream
+    #[default]
+    FfOnly,
+    /// Rebase the local commits of the checked out branch onto its upstream
+    Rebase,
+    /// Reset the checked out branch and working tree to its upstream, discarding local changes
+    ResetHard,
+}
+
+impl fmt::Display for UpdateStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = match self {
+            UpdateStrategy::Fetch => "fetch",
+            UpdateStrategy::FfOnly => "ff-only",
+            UpdateStrategy::Rebase => "rebase",
+            UpdateStrategy::ResetHard => "reset-hard",
+        };
+
+        write!(f, "{}", result)
+    }
+}
+
+impl FromStr for UpdateStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "fetch" => Ok(UpdateStrategy::Fetch),
+            "ff-only" => Ok(UpdateStrategy::FfOnly),
+            "rebase" => Ok(UpdateStrategy::Rebase),
+            "reset-hard" => Ok(UpdateStrategy::ResetHard),
+            _ => Err(Error::InvalidValue(format!(
+                "'{}' is not an update strategy: [fetch, ff-only, rebase, reset-hard]",
+                s
+            ))),
+        }
+    }
+}
@@ -5,23 +5,33 @@
 extern crate log;
 
 pub use cache::Cache;
+pub use error::{Error, Result};
+pub use git::{find_repositories, Backend, CredentialProvider, Status, UpdateStrategy};
 pub use location::Location;
-pub use query::{Query, ScpPath};
+pub use manifest::{Change, Manifest, ManifestFormat, Plan};
+pub use query::{AliasUrl, Layout, Query, Scheme, ScpPath};
 pub use remote::Remote;
 pub use repository::{Repository, RepositoryBuilder};
+pub use state::{HookStatus, State};
 pub use tag::{Tag, TagBuilder};
-pub use workspace::Workspace;
+pub use workspace::{GoneBranches, MirrorGc, UpdateOptions, UpdateStatus, Workspace};
 
 pub mod prelude;
+pub mod progress;
 pub mod shell;
 pub mod util;
 
 mod cache;
 mod config;
+mod error;
 mod git;
 mod location;
+mod manifest;
+mod mirror;
 mod query;
 mod remote;
 mod repository;
+mod resolve;
+mod state;
 mod tag;
 mod workspace;
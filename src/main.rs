@@ -5,15 +5,39 @@ use std::{
 
 use clap::Parser;
 use cmd::{Cli, Run};
+use log::{Log, Metadata, Record};
 
 mod cmd;
 
+/// Clears the progress status line before a log record is written
+struct ProgressLogger<L>(L);
+
+impl<L: Log> Log for ProgressLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.0.enabled(record.metadata()) {
+            repo_cli::progress::suspend(|| self.0.log(record));
+        }
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
 fn main() -> ExitCode {
     if std::env::var("RUST_LOG").is_err() {
         std::env::set_var("RUST_LOG", "warn");
     }
 
-    pretty_env_logger::init();
+    let logger = pretty_env_logger::formatted_builder()
+        .parse_default_env()
+        .build();
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(ProgressLogger(logger))).expect("failed to set logger");
 
     match Cli::parse().run() {
         Ok(_) => ExitCode::SUCCESS,
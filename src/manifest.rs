@@ -0,0 +1,377 @@
+use crate::{error::Context, Error, Result};
+use crate::{Cache, Location, Repository, Tag};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// A single document describing every repository and tag of a workspace
+///
+/// Entries are grouped by the cache location they are written to.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default, skip_serializing_if = "ManifestSection::is_empty")]
+    pub global: ManifestSection,
+    #[serde(default, skip_serializing_if = "ManifestSection::is_empty")]
+    pub local: ManifestSection,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ManifestSection {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repositories: Vec<Repository>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Tag>,
+}
+
+/// Format used to read and write a manifest
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ManifestFormat {
+    Toml,
+    Json,
+}
+
+/// A change to an entry of the cache
+#[derive(Debug)]
+pub enum Change<T> {
+    Add(T),
+    Update { old: T, new: T },
+    Remove(T),
+}
+
+/// The changes required to make the cache match a manifest
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub repositories: Vec<Change<Repository>>,
+    pub tags: Vec<Change<Tag>>,
+}
+
+impl Manifest {
+    pub fn from_cache(cache: &Cache) -> Self {
+        let mut manifest = Manifest::default();
+
+        let mut repositories = cache.repositories();
+        repositories.sort_by(|a, b| a.name.cmp(&b.name));
+        for repository in repositories {
+            manifest
+                .section_mut(repository.location)
+                .repositories
+                .push(repository.clone());
+        }
+
+        let mut tags = cache.tags();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        for tag in tags {
+            manifest.section_mut(tag.location).tags.push(tag.clone());
+        }
+
+        manifest
+    }
+
+    pub fn parse(content: &str, format: ManifestFormat) -> Result<Self> {
+        let mut manifest: Manifest = match format {
+            ManifestFormat::Toml => {
+                toml::from_str(content).context("could not deserialize content into Manifest")?
+            }
+            ManifestFormat::Json => serde_json::from_str(content)
+                .context("could not deserialize content into Manifest")?,
+        };
+
+        for location in [Location::Global, Location::Local] {
+            let section = manifest.section_mut(location);
+            for repository in section.repositories.iter_mut() {
+                repository.location = location;
+                repository.config = Repository::path_from_location(location)
+                    .join(format!("{}.toml", repository.name));
+            }
+
+            for tag in section.tags.iter_mut() {
+                tag.location = location;
+                tag.config = Tag::path_from_location(location).join(format!("{}.toml", tag.name));
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn to_string(&self, format: ManifestFormat) -> Result<String> {
+        match format {
+            ManifestFormat::Toml => {
+                toml::to_string_pretty(self).context("failed to serialize manifest")
+            }
+            ManifestFormat::Json => {
+                serde_json::to_string_pretty(self).context("failed to serialize manifest")
+            }
+        }
+    }
+
+    /// Compute the changes needed for the cache to match this manifest
+    ///
+    /// Entries that are tracked in the cache but not defined in the manifest are only removed
+    /// when `prune` is set.
+    pub fn plan(&self, cache: &Cache, prune: bool) -> Result<Plan> {
+        let mut plan = Plan::default();
+        let mut repository_names = BTreeSet::new();
+        let mut tag_names = BTreeSet::new();
+
+        for section in [&self.global, &self.local] {
+            for repository in &section.repositories {
+                if !repository_names.insert(repository.name.as_str()) {
+                    return Err(Error::InvalidValue(format!(
+                        "Repository: '{}' is defined more than once in manifest",
+                        repository.name
+                    )));
+                }
+
+                match cache.get_repository(&repository.name) {
+                    Some(old) if old == repository => {}
+                    Some(old) => plan.repositories.push(Change::Update {
+                        old: old.clone(),
+                        new: repository.clone(),
+                    }),
+                    None => plan.repositories.push(Change::Add(repository.clone())),
+                }
+            }
+
+            for tag in &section.tags {
+                if !tag_names.insert(tag.name.as_str()) {
+                    return Err(Error::InvalidValue(format!(
+                        "Tag: '{}' is defined more than once in manifest",
+                        tag.name
+                    )));
+                }
+
+                match cache.get_tag(&tag.name) {
+                    Some(old) if old == tag => {}
+                    Some(old) => plan.tags.push(Change::Update {
+                        old: old.clone(),
+                        new: tag.clone(),
+                    }),
+                    None => plan.tags.push(Change::Add(tag.clone())),
+                }
+            }
+        }
+
+        if prune {
+            let mut repositories = cache.repositories();
+            repositories.sort_by(|a, b| a.name.cmp(&b.name));
+            for repository in repositories {
+                if !repository_names.contains(repository.name.as_str()) {
+                    plan.repositories.push(Change::Remove(repository.clone()));
+                }
+            }
+
+            let mut tags = cache.tags();
+            tags.sort_by(|a, b| a.name.cmp(&b.name));
+            for tag in tags {
+                if !tag_names.contains(tag.name.as_str()) {
+                    plan.tags.push(Change::Remove(tag.clone()));
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+
+    fn section_mut(&mut self, location: Location) -> &mut ManifestSection {
+        match location {
+            Location::Global => &mut self.global,
+            Location::Local => &mut self.local,
+        }
+    }
+}
+
+impl ManifestSection {
+    pub fn is_empty(&self) -> bool {
+        self.repositories.is_empty() && self.tags.is_empty()
+    }
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.repositories.is_empty() && self.tags.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sets_location() -> Result<()> {
+        let content = r#"
+[[global.repositories]]
+name = "repo"
+tags = []
+
+[[global.repositories.remotes]]
+name = "origin"
+url = "https://github.com/edeneast/repo"
+
+[[local.tags]]
+name = "work"
+priority = 10
+"#;
+        let manifest = Manifest::parse(content, ManifestFormat::Toml)?;
+
+        assert_eq!(manifest.global.repositories.len(), 1);
+        assert_eq!(manifest.global.repositories[0].location, Location::Global);
+        assert!(manifest.global.repositories[0]
+            .config
+            .ends_with("repo.toml"));
+        assert_eq!(manifest.local.tags.len(), 1);
+        assert_eq!(manifest.local.tags[0].location, Location::Local);
+        assert!(manifest.local.repositories.is_empty());
+        Ok(())
+    }
+
+    fn cache(content: &str) -> Result<Cache> {
+        let manifest = Manifest::parse(content, ManifestFormat::Toml)?;
+        let mut cache = Cache::default();
+        for section in [manifest.global, manifest.local] {
+            section
+                .repositories
+                .into_iter()
+                .for_each(|r| cache.add_repository(r));
+            section.tags.into_iter().for_each(|t| cache.add_tag(t));
+        }
+
+        Ok(cache)
+    }
+
+    fn names<T>(changes: &[Change<T>], name: fn(&T) -> String) -> Vec<String> {
+        changes
+            .iter()
+            .map(|change| match change {
+                Change::Add(new) => format!("add {}", name(new)),
+                Change::Update { new, .. } => format!("update {}", name(new)),
+                Change::Remove(old) => format!("remove {}", name(old)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plan_changes() -> Result<()> {
+        let cache = cache(
+            r#"
+[[global.repositories]]
+name = "same"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/same" }]
+
+[[global.repositories]]
+name = "changed"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/changed" }]
+
+[[global.repositories]]
+name = "untracked"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/untracked" }]
+
+[[global.tags]]
+name = "work"
+priority = 10
+"#,
+        )?;
+
+        let manifest = Manifest::parse(
+            r#"
+[[global.repositories]]
+name = "same"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/same" }]
+
+[[global.repositories]]
+name = "changed"
+tags = ["work"]
+remotes = [{ name = "origin", url = "https://github.com/edeneast/changed" }]
+
+[[global.repositories]]
+name = "new"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/new" }]
+
+[[global.tags]]
+name = "work"
+priority = 20
+"#,
+            ManifestFormat::Toml,
+        )?;
+
+        let repository = |r: &Repository| r.name.clone();
+        let tag = |t: &Tag| t.name.clone();
+
+        let plan = manifest.plan(&cache, false)?;
+        assert_eq!(
+            names(&plan.repositories, repository),
+            vec!["update changed", "add new"]
+        );
+        assert_eq!(names(&plan.tags, tag), vec!["update work"]);
+
+        let plan = manifest.plan(&cache, true)?;
+        assert_eq!(
+            names(&plan.repositories, repository),
+            vec!["update changed", "add new", "remove untracked"]
+        );
+
+        assert!(Manifest::default()
+            .plan(&Cache::default(), true)?
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn plan_location_change() -> Result<()> {
+        let cache = cache(
+            r#"
+[[local.repositories]]
+name = "repo"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/repo" }]
+"#,
+        )?;
+
+        let manifest = Manifest::parse(
+            r#"
+[[global.repositories]]
+name = "repo"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/repo" }]
+"#,
+            ManifestFormat::Toml,
+        )?;
+
+        // NOTE: Applying an update with a different config path removes the old cache file
+        let plan = manifest.plan(&cache, false)?;
+        match plan.repositories.as_slice() {
+            [Change::Update { old, new }] => {
+                assert_eq!(old.location, Location::Local);
+                assert_eq!(new.location, Location::Global);
+                assert_ne!(old.config, new.config);
+            }
+            changes => panic!("expected a single update, got: {:?}", changes),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn plan_rejects_duplicates() -> Result<()> {
+        let manifest = Manifest::parse(
+            r#"
+[[global.repositories]]
+name = "repo"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/repo" }]
+
+[[local.repositories]]
+name = "repo"
+tags = []
+remotes = [{ name = "origin", url = "https://github.com/edeneast/repo" }]
+"#,
+            ManifestFormat::Toml,
+        )?;
+
+        let error = manifest.plan(&Cache::default(), false).unwrap_err();
+        assert!(matches!(error, Error::InvalidValue(_)));
+        Ok(())
+    }
+}
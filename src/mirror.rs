@@ -0,0 +1,151 @@
+use crate::git;
+use crate::{error::Context, Result};
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use url::Url;
+
+lazy_static! {
+    /// Locks of the mirrors, repositories that share a remote update the same mirror
+    static ref LOCKS: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>> = Mutex::new(HashMap::new());
+}
+
+/// Directory of bare mirrors, one for every remote url
+///
+/// A mirror is stored at `<root>/<host>/<path>.git`. Urls with different schemes that point to
+/// the same repository share a mirror.
+#[derive(Debug)]
+pub struct Mirror {
+    root: PathBuf,
+}
+
+impl Mirror {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self, url: &Url) -> PathBuf {
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}_{}", host, port),
+            (Some(host), None) => host.to_owned(),
+            _ => "local".to_owned(),
+        };
+
+        let mut segments: Vec<String> = url
+            .path()
+            .trim_end_matches(".git")
+            .split('/')
+            .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+            .map(sanitize)
+            .collect();
+
+        if let Some(last) = segments.last_mut() {
+            last.push_str(".git");
+        }
+
+        let mut path = self.root.join(sanitize(&host));
+        path.extend(segments);
+        path
+    }
+
+    /// Create or update the mirror of a url, returning its path
+    pub fn update(&self, url: &Url, use_cli: bool) -> Result<PathBuf> {
+        let path = self.path(url);
+        let lock = lock(&path);
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        debug!("Updating mirror: {}", path.display());
+        git::mirror(&path, url.as_str(), use_cli)?;
+        Ok(path)
+    }
+
+    /// Paths of every mirror in the directory
+    pub fn mirrors(&self) -> Result<Vec<PathBuf>> {
+        let mut result = Vec::new();
+        if self.root.is_dir() {
+            find_mirrors(&self.root, &mut result)?;
+        }
+
+        result.sort();
+        Ok(result)
+    }
+
+    /// Remove a mirror and the folders that are left empty by it
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        std::fs::remove_dir_all(path)
+            .context(format!("failed to remove mirror: {}", path.display()))?;
+
+        let mut parent = path.parent();
+        while let Some(dir) = parent {
+            if dir == self.root || std::fs::remove_dir(dir).is_err() {
+                break;
+            }
+            parent = dir.parent();
+        }
+
+        Ok(())
+    }
+}
+
+fn lock(path: &Path) -> Arc<Mutex<()>> {
+    LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(path.to_path_buf())
+        .or_default()
+        .clone()
+}
+
+fn sanitize(segment: &str) -> String {
+    segment
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+fn find_mirrors(dir: &Path, result: &mut Vec<PathBuf>) -> Result<()> {
+    if dir.join("HEAD").is_file() && dir.join("objects").is_dir() {
+        result.push(dir.to_path_buf());
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            find_mirrors(&entry.path(), result)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirror_path() -> Result<()> {
+        let mirror = Mirror::new("/mirror");
+
+        let https: Url = "https://github.com/edeneast/repo".parse()?;
+        let ssh: Url = "ssh://git@github.com/edeneast/repo.git".parse()?;
+        let file: Url = "file:///srv/git/repo.git".parse()?;
+
+        let expected = PathBuf::from("/mirror/github.com/edeneast/repo.git");
+        assert_eq!(mirror.path(&https), expected);
+        assert_eq!(mirror.path(&ssh), expected);
+        assert_eq!(
+            mirror.path(&file),
+            PathBuf::from("/mirror/local/srv/git/repo.git")
+        );
+        Ok(())
+    }
+}
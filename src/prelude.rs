@@ -1,4 +1,6 @@
 pub use crate::{
-    config::Config, Cache, Location, Query, Remote, Repository, RepositoryBuilder, ScpPath, Tag,
+    config::{Config, HostAlias, NameRule},
+    Backend, Cache, CredentialProvider, GoneBranches, Layout, Location, Query, Remote, Repository,
+    RepositoryBuilder, Scheme, ScpPath, Status, Tag, UpdateOptions, UpdateStatus, UpdateStrategy,
     Workspace,
 };
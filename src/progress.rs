@@ -0,0 +1,260 @@
+//! Progress of the git transfers made while updating repositories
+//!
+//! Repositories are updated on worker threads, so the repository a transfer belongs to is tracked
+//! per thread with a [`Scope`]. When stderr is a terminal a single status line shows the latest
+//! progress of any repository, otherwise a line is logged periodically for long transfers.
+
+use lazy_static::lazy_static;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    io::{IsTerminal, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Minimum time between two redraws of the status line
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Minimum time between two log lines of the same repository when stderr is not a terminal
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref DISPLAY: Mutex<Display> = Mutex::new(Display::new());
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Show the progress of git transfers, it is hidden by default
+pub fn enable(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Clear the status line so that other output starts on an empty line
+pub fn finish() {
+    DISPLAY.lock().expect("progress lock poisoned").clear();
+}
+
+/// Write other output to stderr without it being glued to the status line
+///
+/// The status line is cleared first and redrawn by the next progress report.
+pub fn suspend<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let mut display = DISPLAY.lock().expect("progress lock poisoned");
+    display.clear();
+    f()
+}
+
+/// Step of a git transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Stage<'a> {
+    /// Objects downloaded from the remote
+    Receiving {
+        received: usize,
+        total: usize,
+        bytes: usize,
+    },
+    /// Deltas of the downloaded objects resolved
+    Resolving { indexed: usize, total: usize },
+    /// Message sent by the remote, like `Counting objects`
+    Remote(&'a str),
+    /// Files written to the working tree
+    Checkout { current: usize, total: usize },
+}
+
+impl fmt::Display for Stage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Stage::Receiving {
+                received,
+                total,
+                bytes,
+            } => write!(
+                f,
+                "receiving objects {}% ({}/{}), {}",
+                percent(received, total),
+                received,
+                total,
+                format_bytes(bytes)
+            ),
+            Stage::Resolving { indexed, total } => write!(
+                f,
+                "resolving deltas {}% ({}/{})",
+                percent(indexed, total),
+                indexed,
+                total
+            ),
+            Stage::Remote(message) => write!(f, "remote: {}", message),
+            Stage::Checkout { current, total } => write!(
+                f,
+                "checking out files {}% ({}/{})",
+                percent(current, total),
+                current,
+                total
+            ),
+        }
+    }
+}
+
+/// Attributes the progress reported on the current thread to a repository until dropped
+pub(crate) struct Scope;
+
+impl Scope {
+    pub(crate) fn new(name: &str) -> Self {
+        CURRENT.with(|current| *current.borrow_mut() = Some(name.to_owned()));
+        DISPLAY.lock().expect("progress lock poisoned").start(name);
+        Scope
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.borrow_mut().take());
+    }
+}
+
+/// Report the progress of the repository of the current thread
+///
+/// Nothing is shown if progress is disabled or the thread is not in a [`Scope`].
+pub(crate) fn report(stage: Stage) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    CURRENT.with(|current| {
+        if let Some(name) = current.borrow().as_deref() {
+            DISPLAY
+                .lock()
+                .expect("progress lock poisoned")
+                .show(name, stage);
+        }
+    });
+}
+
+struct Display {
+    interactive: bool,
+    drawn: bool,
+    last_draw: Option<Instant>,
+    last_log: HashMap<String, Instant>,
+}
+
+impl Display {
+    fn new() -> Self {
+        Self {
+            interactive: std::io::stderr().is_terminal(),
+            drawn: false,
+            last_draw: None,
+            last_log: HashMap::new(),
+        }
+    }
+
+    fn start(&mut self, name: &str) {
+        // NOTE: Logging waits a full interval so that quick transfers stay silent
+        self.last_log.insert(name.to_owned(), Instant::now());
+    }
+
+    fn show(&mut self, name: &str, stage: Stage) {
+        let now = Instant::now();
+        let mut stderr = std::io::stderr();
+
+        if self.interactive {
+            if self
+                .last_draw
+                .is_some_and(|last| now.duration_since(last) < REDRAW_INTERVAL)
+            {
+                return;
+            }
+
+            let line = format!("{:>20.20} | {}", name, stage);
+            let line: String = line
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(100)
+                .collect();
+            let _ = write!(stderr, "\r\x1b[K{}", line);
+            let _ = stderr.flush();
+            self.drawn = true;
+            self.last_draw = Some(now);
+        } else {
+            // NOTE: Messages from the remote are only useful next to the transfer on a status line
+            if let Stage::Remote(_) = stage {
+                return;
+            }
+
+            let last = self.last_log.entry(name.to_owned()).or_insert(now);
+            if now.duration_since(*last) < LOG_INTERVAL {
+                return;
+            }
+
+            let _ = writeln!(stderr, "{:>20.20} | {}", name, stage);
+            *last = now;
+        }
+    }
+
+    fn clear(&mut self) {
+        if self.drawn {
+            let mut stderr = std::io::stderr();
+            let _ = write!(stderr, "\r\x1b[K");
+            let _ = stderr.flush();
+            self.drawn = false;
+        }
+    }
+}
+
+fn percent(current: usize, total: usize) -> usize {
+    (current * 100).checked_div(total).unwrap_or(100)
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_stage() {
+        let stage = Stage::Receiving {
+            received: 450,
+            total: 1000,
+            bytes: 3 * 1024 * 1024 + 300 * 1024,
+        };
+        assert_eq!(
+            stage.to_string(),
+            "receiving objects 45% (450/1000), 3.3 MiB"
+        );
+
+        let stage = Stage::Resolving {
+            indexed: 0,
+            total: 0,
+        };
+        assert_eq!(stage.to_string(), "resolving deltas 100% (0/0)");
+        assert_eq!(format_bytes(512), "512 B");
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     config::Config,
     query::{AbbrevUrl, Scheme},
 };
-use anyhow::{anyhow, Error, Result};
+use crate::{Error, Result};
 use regex::Regex;
 use std::str::FromStr;
 use url::Url;
@@ -12,8 +12,12 @@ impl AbbrevUrl {
         s.parse()
     }
 
-    pub fn to_url(&self, config: &Config) -> Url {
+    pub fn to_url(&self, config: &Config) -> Result<Url> {
         let host = config.host(None);
+        if let Some(alias) = config.host_alias_by_host(host) {
+            return alias.to_url(&format!("{}/{}", self.username, self.path), config);
+        }
+
         let scheme = config.scheme(None);
 
         let url_string = match scheme {
@@ -37,7 +41,8 @@ impl AbbrevUrl {
             ),
         };
 
-        Url::parse(&url_string).unwrap()
+        Url::parse(&url_string)
+            .map_err(|e| Error::InvalidQuery(format!("invalid url '{}': {}", url_string, e)))
     }
 }
 
@@ -48,9 +53,9 @@ impl FromStr for AbbrevUrl {
         // https://regex101.com/r/1AKIVV/1
         let regex = Regex::new(r"^([^/]+)/(.*)")?;
 
-        let captures = regex
-            .captures(s)
-            .ok_or_else(|| anyhow!("path: {} does not match Abbrev url regex", s))?;
+        let captures = regex.captures(s).ok_or_else(|| {
+            Error::InvalidQuery(format!("path: {} does not match Abbrev url regex", s))
+        })?;
 
         let username = captures.get(1).map(|s| s.as_str().to_owned()).unwrap();
         let path = captures.get(2).map(|s| s.as_str().to_owned()).unwrap();
@@ -0,0 +1,130 @@
+use crate::{
+    config::{Config, HostAlias},
+    query::{inner::URL_SCHEMES, template, AliasUrl, Scheme},
+};
+use crate::{Error, Result};
+use regex::Regex;
+use std::str::FromStr;
+use url::Url;
+
+/// Pattern of an alias name
+///
+/// Queries are parsed as urls first, so an alias must also be a valid url scheme.
+const ALIAS_NAME: &str = "[A-Za-z][A-Za-z0-9+-]*";
+
+impl AliasUrl {
+    pub fn parse(s: &str) -> Result<Self> {
+        s.parse()
+    }
+
+    /// Check if a host alias with this name can be used in a query
+    pub fn is_valid_alias(name: &str) -> bool {
+        !Self::is_reserved_alias(name)
+            && Regex::new(&format!("^{}$", ALIAS_NAME))
+                .map(|regex| regex.is_match(name))
+                .unwrap_or(false)
+    }
+
+    /// Check if the name is a url scheme, a query with it is always parsed as a url
+    pub fn is_reserved_alias(name: &str) -> bool {
+        URL_SCHEMES.iter().any(|s| s.eq_ignore_ascii_case(name))
+    }
+
+    pub fn to_url(&self, config: &Config) -> Result<Url> {
+        config
+            .host_alias(&self.alias)
+            .ok_or_else(|| {
+                Error::InvalidQuery(format!("'{}' is not a defined host alias", self.alias))
+            })?
+            .to_url(&self.path, config)
+    }
+}
+
+impl HostAlias {
+    /// Create the url of a repository path on the aliased host
+    pub fn to_url(&self, path: &str, config: &Config) -> Result<Url> {
+        let scheme = self.scheme.unwrap_or_else(|| config.scheme(None));
+        let path = match &self.template {
+            Some(t) => template::expand(t, path),
+            None => path.to_owned(),
+        };
+        let path = path.trim_start_matches('/');
+
+        let url_string = match scheme {
+            Scheme::Ssh => {
+                let ssh_user = self
+                    .ssh_user
+                    .as_deref()
+                    .unwrap_or_else(|| config.ssh_user(None));
+                format!(
+                    "{}{}@{}/{}",
+                    scheme.to_url_scheme(),
+                    ssh_user,
+                    self.host,
+                    path
+                )
+            }
+            _ => format!("{}{}/{}", scheme.to_url_scheme(), self.host, path),
+        };
+
+        Url::parse(&url_string).map_err(|e| {
+            Error::InvalidQuery(format!(
+                "host '{}' produced an invalid url '{}': {}",
+                self.host, url_string, e
+            ))
+        })
+    }
+}
+
+impl FromStr for AliasUrl {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let regex = Regex::new(&format!("^({}):([^/].*)$", ALIAS_NAME))?;
+
+        let captures = regex.captures(s).ok_or_else(|| {
+            Error::InvalidQuery(format!("query: {} does not match host alias regex", s))
+        })?;
+
+        let alias = captures.get(1).unwrap().as_str().to_owned();
+        let path = captures
+            .get(2)
+            .unwrap()
+            .as_str()
+            .trim_end_matches(".git")
+            .to_owned();
+
+        Ok(Self { alias, path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let alias = AliasUrl::from_str("gl:infra/terraform.git").unwrap();
+
+        assert_eq!(alias.alias, "gl");
+        assert_eq!(alias.path, "infra/terraform");
+        assert!(AliasUrl::from_str("https://github.com/edeneast/repo").is_err());
+        assert!(AliasUrl::from_str("git@github.com:edeneast/repo").is_err());
+    }
+
+    #[test]
+    fn alias_names() {
+        assert!(AliasUrl::is_valid_alias("gl"));
+        assert!(AliasUrl::is_valid_alias("work-2"));
+        assert!(!AliasUrl::is_valid_alias("my_host"));
+        assert!(!AliasUrl::is_valid_alias("1work"));
+        assert!(AliasUrl::from_str("my_host:org/repo").is_err());
+        assert!(AliasUrl::from_str("1work:repo").is_err());
+
+        for scheme in &["git", "ssh", "http", "https", "file", "SSH"] {
+            assert!(AliasUrl::is_reserved_alias(scheme));
+            assert!(!AliasUrl::is_valid_alias(scheme));
+        }
+        assert!(!AliasUrl::is_reserved_alias("github"));
+    }
+}
@@ -1,20 +1,25 @@
 use crate::{
     config::Config,
-    query::{AbbrevUrl, Query, ScpPath},
+    query::{AbbrevUrl, AliasUrl, Query, ScpPath},
+    util,
 };
-use anyhow::{anyhow, Error, Result};
-use std::str::FromStr;
+use crate::{Error, Result};
+use std::{path::Path, str::FromStr};
 use url::Url;
 
+/// Url schemes accepted in a query, they cannot be used as host alias names
+pub(crate) const URL_SCHEMES: &[&str] = &["http", "https", "git", "ssh", "file"];
+
 impl Query {
     pub fn parse(s: &str) -> Result<Self> {
         s.parse()
     }
 
-    pub fn to_url(&self, config: &Config) -> Url {
+    pub fn to_url(&self, config: &Config) -> Result<Url> {
         match self {
-            Query::Url(url) => url.clone(),
-            Query::Scp(scp) => scp.to_url(),
+            Query::Url(url) => Ok(url.clone()),
+            Query::Scp(scp) => Ok(scp.to_url()),
+            Query::Alias(alias) => alias.to_url(config),
             Query::Abbrev(abbrev) => abbrev.to_url(config),
         }
     }
@@ -26,16 +31,81 @@ impl FromStr for Query {
     fn from_str(s: &str) -> Result<Self> {
         if let Ok(url) = Url::parse(s) {
             match url.scheme() {
-                "http" | "https" | "git" | "ssh" => {}
-                scheme => return Err(anyhow!("'{}' is an invalid scheme", scheme)),
+                scheme if URL_SCHEMES.contains(&scheme) => {}
+                _ if AliasUrl::parse(s).is_ok() => return Ok(Query::Alias(AliasUrl::parse(s)?)),
+                scheme => {
+                    return Err(Error::InvalidQuery(format!(
+                        "'{}' is an invalid scheme",
+                        scheme
+                    )))
+                }
             }
 
             return Ok(Query::Url(url));
+        } else if is_local_path(s) {
+            return local_path_url(s).map(Query::Url);
         } else if let Ok(scp) = ScpPath::parse(s) {
             return Ok(Query::Scp(scp));
         } else if let Ok(abbrev) = AbbrevUrl::parse(s) {
             return Ok(Query::Abbrev(abbrev));
         }
-        Err(anyhow!("'{}' invalid query not url or scp path", s))
+        Err(Error::InvalidQuery(format!(
+            "'{}' invalid query not url or scp path",
+            s
+        )))
+    }
+}
+
+/// Check if the query points to a repository on the local filesystem
+///
+/// Paths that start with `/`, `./`, `../` or `~` are always local. Any other relative path is
+/// only local if it is a git repository, as `user/repo` is otherwise an abbreviated url.
+fn is_local_path(s: &str) -> bool {
+    if s == "." || s == ".." || ["/", "./", "../", "~"].iter().any(|p| s.starts_with(p)) {
+        return true;
+    }
+
+    let path = Path::new(s);
+    path.join("HEAD").is_file() || path.join(".git").exists()
+}
+
+fn local_path_url(s: &str) -> Result<Url> {
+    let path = util::make_path_buf(s)?;
+    let path = if path.is_absolute() {
+        path
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let path = path.canonicalize().unwrap_or(path);
+
+    Url::from_file_path(&path).map_err(|_| {
+        Error::InvalidQuery(format!(
+            "'{}' can not be converted into a file url",
+            path.display()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_paths() -> Result<()> {
+        let url = match Query::parse("file:///srv/git/repo.git")? {
+            Query::Url(url) => url,
+            query => panic!("expected url, found: {:?}", query),
+        };
+        assert_eq!(url.scheme(), "file");
+        assert_eq!(url.path(), "/srv/git/repo.git");
+
+        let url = match Query::parse("/srv/git/repo.git")? {
+            Query::Url(url) => url,
+            query => panic!("expected url, found: {:?}", query),
+        };
+        assert_eq!(url.as_str(), "file:///srv/git/repo.git");
+
+        assert!(matches!(Query::parse("edeneast/repo")?, Query::Abbrev(_)));
+        Ok(())
     }
 }
@@ -0,0 +1,145 @@
+use crate::{
+    config::{Config, HostAlias, NameRule},
+    query::{template, Layout, Query},
+};
+use crate::{Error, Result};
+use std::path::PathBuf;
+
+impl Query {
+    /// Default repository name and workspace path of the query
+    ///
+    /// The host alias of the query's host decides how the name is derived and if the group of the
+    /// path is used as the workspace path. Urls are matched against the alias' template so that
+    /// parts like `_git` or `scm` are not mistaken for a group or name.
+    ///
+    /// The group path is stored as the path of the repository, which takes precedence over the
+    /// path of its tags. Callers should only use it when no tag of the repository has a path.
+    pub fn layout(&self, config: &Config) -> Result<Layout> {
+        let (alias, path) = match self {
+            Query::Url(url) => {
+                let host = match (url.host_str(), url.port()) {
+                    (Some(host), Some(port)) => format!("{}:{}", host, port),
+                    (Some(host), None) => host.to_owned(),
+                    _ => String::new(),
+                };
+                let alias = config.host_alias_by_host(&host);
+                (alias, path_from_url(alias, url.path()))
+            }
+            Query::Scp(scp) => {
+                let alias = config.host_alias_by_host(&scp.host);
+                (alias, path_from_url(alias, &scp.path))
+            }
+            Query::Alias(alias) => (config.host_alias(&alias.alias), alias.path.clone()),
+            Query::Abbrev(abbrev) => (
+                config.host_alias_by_host(config.host(None)),
+                format!("{}/{}", abbrev.username, abbrev.path),
+            ),
+        };
+
+        let segments: Vec<&str> = path
+            .trim_end_matches(".git")
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let (name, group) = segments.split_last().ok_or_else(|| {
+            Error::InvalidQuery(format!(
+                "could not derive a repository name from '{}'",
+                path
+            ))
+        })?;
+
+        let rule = alias.and_then(|a| a.name).unwrap_or(NameRule::Last);
+        let name = match rule {
+            NameRule::Last => name.to_string(),
+            NameRule::Full => segments.join("-"),
+        };
+
+        let path = match alias.and_then(|a| a.group_path) {
+            Some(true) if !group.is_empty() => Some(PathBuf::from(group.join("/"))),
+            _ => None,
+        };
+
+        Ok(Layout { name, path })
+    }
+}
+
+fn path_from_url(alias: Option<&HostAlias>, url_path: &str) -> String {
+    alias
+        .and_then(|a| a.template.as_deref())
+        .and_then(|t| template::extract(t, url_path))
+        .unwrap_or_else(|| url_path.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[hosts.gl]
+host = 'gitlab.com'
+
+[hosts.full]
+host = 'full.example.com'
+name = 'full'
+
+[hosts.group]
+host = 'group.example.com'
+group_path = true
+
+[hosts.az]
+host = 'dev.azure.com'
+template = '{group}/_git/{name}'
+group_path = true
+"#;
+
+    fn layout_of(query: &str) -> Layout {
+        let config = Config::from_toml(CONFIG);
+        Query::parse(query).unwrap().layout(&config).unwrap()
+    }
+
+    #[test]
+    fn name_last() {
+        let layout = layout_of("gl:org/group/project");
+        assert_eq!(layout.name, "project");
+        assert_eq!(layout.path, None);
+
+        let layout = layout_of("https://gitlab.com/org/project.git");
+        assert_eq!(layout.name, "project");
+        assert_eq!(layout.path, None);
+    }
+
+    #[test]
+    fn name_full() {
+        assert_eq!(
+            layout_of("full:org/group/project").name,
+            "org-group-project"
+        );
+        assert_eq!(
+            layout_of("https://full.example.com/org/project.git").name,
+            "org-project"
+        );
+    }
+
+    #[test]
+    fn group_path_nested() {
+        let layout = layout_of("group:org/sub/deep/project");
+        assert_eq!(layout.name, "project");
+        assert_eq!(layout.path, Some(PathBuf::from("org/sub/deep")));
+
+        let layout = layout_of("git@group.example.com:org/sub/project.git");
+        assert_eq!(layout.name, "project");
+        assert_eq!(layout.path, Some(PathBuf::from("org/sub")));
+
+        let layout = layout_of("group:project");
+        assert_eq!(layout.name, "project");
+        assert_eq!(layout.path, None);
+    }
+
+    #[test]
+    fn group_path_template() {
+        let layout = layout_of("https://dev.azure.com/org/project/_git/repo");
+        assert_eq!(layout.name, "repo");
+        assert_eq!(layout.path, Some(PathBuf::from("org/project")));
+    }
+}
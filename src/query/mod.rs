@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use url::Url;
 
 /// Represents a input url from the user.
@@ -12,11 +13,16 @@ use url::Url;
 ///   - Equivalent to `ssh://<username>@<host>/<path-to-repo>.git`
 /// * `<username>@<host>:<path-to-repo>`
 ///   - Equivalent to `ssh://<username>@<host>/<path-to-repo>.git`
+/// * `file://<path-to-repo>` or a path to a repository on the local filesystem
+///   - Relative paths must start with `./` or `../` unless they point to an existing repository
+/// * `<alias>:<path-to-repo>`
+///   - Expanded with the host alias named `<alias>` defined in the config
 /// * `<path-to-repo>`
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Query {
     Url(Url),
     Scp(ScpPath),
+    Alias(AliasUrl),
     Abbrev(AbbrevUrl),
 }
 
@@ -27,12 +33,25 @@ pub struct ScpPath {
     pub path: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AliasUrl {
+    pub alias: String,
+    pub path: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AbbrevUrl {
     pub username: String,
     pub path: String,
 }
 
+/// Default name and workspace path of a repository derived from a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub name: String,
+    pub path: Option<PathBuf>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 pub enum Scheme {
     #[serde(rename = "http")]
@@ -46,6 +65,9 @@ pub enum Scheme {
 }
 
 mod abbrev;
+mod alias;
 mod inner;
+mod layout;
 mod scheme;
 mod scp;
+mod template;
@@ -1,5 +1,5 @@
 use crate::query::Scheme;
-use anyhow::{anyhow, Error, Result};
+use crate::{Error, Result};
 use std::{fmt, str::FromStr};
 
 impl Scheme {
@@ -35,7 +35,10 @@ impl FromStr for Scheme {
             "http" => Ok(Scheme::Http),
             "https" => Ok(Scheme::Https),
             "ssh" => Ok(Scheme::Ssh),
-            _ => Err(anyhow!("failed to convert: {} into a valid scheme", s)),
+            _ => Err(Error::InvalidQuery(format!(
+                "failed to convert: {} into a valid scheme",
+                s
+            ))),
         }
     }
 }
@@ -1,5 +1,5 @@
 use super::ScpPath;
-use anyhow::{anyhow, Result};
+use crate::{Error, Result};
 use regex::Regex;
 use std::str::FromStr;
 use url::Url;
@@ -11,7 +11,7 @@ impl ScpPath {
 }
 
 impl FromStr for ScpPath {
-    type Err = anyhow::Error;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
         // Example of regex construction: https://regex101.com/r/elsHDo/1
@@ -19,7 +19,7 @@ impl FromStr for ScpPath {
 
         let captures = regex
             .captures(s)
-            .ok_or_else(|| anyhow!("url: {} does not match scp regex", s))?;
+            .ok_or_else(|| Error::InvalidQuery(format!("url: {} does not match scp regex", s)))?;
 
         let username = captures
             .get(1)
@@ -0,0 +1,78 @@
+use regex::Regex;
+
+/// Expand a url path template with the path of a query
+///
+/// `{path}` is replaced with the whole path, `{name}` with its last segment and `{group}` with
+/// the segments before the last one.
+pub fn expand(template: &str, path: &str) -> String {
+    let path = path.trim_matches('/');
+    let (group, name) = match path.rsplit_once('/') {
+        Some((group, name)) => (group, name),
+        None => ("", path),
+    };
+
+    template
+        .replace("{path}", path)
+        .replace("{group}", group)
+        .replace("{name}", name)
+}
+
+/// Recover the path of a query from a url path that was expanded from a template
+pub fn extract(template: &str, url_path: &str) -> Option<String> {
+    let mut pattern = String::from("^/?");
+    let mut rest = template.trim_start_matches('/');
+
+    while let Some(start) = rest.find('{') {
+        pattern.push_str(&regex::escape(&rest[..start]));
+        let end = start + rest[start..].find('}')?;
+        match &rest[start..=end] {
+            "{path}" => pattern.push_str("(?P<path>.+?)"),
+            "{group}" => pattern.push_str("(?P<group>.+?)"),
+            "{name}" => pattern.push_str("(?P<name>[^/]+?)"),
+            other => pattern.push_str(&regex::escape(other)),
+        }
+        rest = &rest[end + 1..];
+    }
+
+    pattern.push_str(&regex::escape(rest));
+    pattern.push_str(r"(?:\.git)?/?$");
+
+    let captures = Regex::new(&pattern).ok()?.captures(url_path)?;
+    if let Some(path) = captures.name("path") {
+        return Some(path.as_str().to_owned());
+    }
+
+    match (captures.name("group"), captures.name("name")) {
+        (Some(group), Some(name)) => Some(format!("{}/{}", group.as_str(), name.as_str())),
+        (None, Some(name)) => Some(name.as_str().to_owned()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_layouts() {
+        assert_eq!(expand("{path}", "org/group/sub/proj"), "org/group/sub/proj");
+        assert_eq!(expand("scm/{path}", "proj/repo"), "scm/proj/repo");
+        assert_eq!(
+            expand("{group}/_git/{name}", "org/project/repo"),
+            "org/project/_git/repo"
+        );
+    }
+
+    #[test]
+    fn extract_layouts() {
+        assert_eq!(
+            extract("{group}/_git/{name}", "/org/project/_git/repo"),
+            Some("org/project/repo".to_owned())
+        );
+        assert_eq!(
+            extract("scm/{path}", "/scm/proj/repo.git"),
+            Some("proj/repo".to_owned())
+        );
+        assert_eq!(extract("scm/{path}", "/other/proj/repo"), None);
+    }
+}
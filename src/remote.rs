@@ -1,5 +1,5 @@
+use crate::Result;
 use crate::{Query, ScpPath};
-use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
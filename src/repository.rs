@@ -1,22 +1,37 @@
-use crate::{config::Config, Cache, Location, Remote, Tag};
-use anyhow::{anyhow, Context, Result};
+use crate::{
+    config::Config, Backend, Cache, CredentialProvider, Location, Remote, Tag, UpdateStrategy,
+};
+use crate::{error::Context, Error, Result};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeSet,
     path::{Path, PathBuf},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Repository {
     pub name: String,
     pub path: Option<PathBuf>,
+    pub branch: Option<String>,
+    pub depth: Option<u32>,
+    pub filter: Option<String>,
+    pub sparse: Option<Vec<String>>,
+    pub submodules: Option<bool>,
+    pub lfs: Option<bool>,
+    pub update_strategy: Option<UpdateStrategy>,
+    pub autostash: Option<bool>,
+    pub credentials: Option<Vec<CredentialProvider>>,
     pub work: Option<String>,
     pub clone: Option<String>,
     pub use_cli: Option<bool>,
+    pub backend: Option<Backend>,
 
     pub tags: BTreeSet<String>,
     pub remotes: Vec<Remote>,
 
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub aliases: BTreeSet<String>,
+
     #[serde(skip)]
     pub config: PathBuf,
 
@@ -28,11 +43,22 @@ pub struct RepositoryBuilder {
     name: String,
     remotes: Vec<Remote>,
     tags: BTreeSet<String>,
+    aliases: BTreeSet<String>,
     location: Location,
     path: Option<PathBuf>,
+    branch: Option<String>,
+    depth: Option<u32>,
+    filter: Option<String>,
+    sparse: Option<Vec<String>>,
+    submodules: Option<bool>,
+    lfs: Option<bool>,
+    update_strategy: Option<UpdateStrategy>,
+    autostash: Option<bool>,
+    credentials: Option<Vec<CredentialProvider>>,
     work: Option<String>,
     clone: Option<String>,
     use_cli: Option<bool>,
+    backend: Option<Backend>,
 }
 
 impl Repository {
@@ -48,6 +74,98 @@ impl Repository {
             .unwrap_or_else(|| PathBuf::from(&self.name))
     }
 
+    /// Branch that should be checked out when cloning the repository
+    ///
+    /// A branch defined on the repository overrides the ones defined by its tags. If no branch
+    /// is defined the default branch of the remote is used.
+    pub fn resolve_branch(&self, cache: &Cache) -> Option<String> {
+        self.branch.clone().or_else(|| {
+            self.resolve_from_tags(cache, |tag| tag.branch.clone())
+                .pop()
+        })
+    }
+
+    /// Number of commits fetched when cloning, `None` fetches the full history
+    pub fn resolve_depth(&self, cache: &Cache) -> Option<u32> {
+        self.depth
+            .or_else(|| self.resolve_from_tags(cache, |tag| tag.depth).pop())
+    }
+
+    /// Partial clone filter spec like `blob:none`
+    pub fn resolve_filter(&self, cache: &Cache) -> Option<String> {
+        self.filter.clone().or_else(|| {
+            self.resolve_from_tags(cache, |tag| tag.filter.clone())
+                .pop()
+        })
+    }
+
+    /// Paths checked out with a sparse checkout, empty if everything is checked out
+    pub fn resolve_sparse(&self, cache: &Cache) -> Vec<String> {
+        self.sparse
+            .clone()
+            .or_else(|| {
+                self.resolve_from_tags(cache, |tag| tag.sparse.clone())
+                    .pop()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Initialize and update submodules recursively on clone and update
+    pub fn resolve_submodules(&self, cache: &Cache) -> bool {
+        self.submodules
+            .or_else(|| self.resolve_from_tags(cache, |tag| tag.submodules).pop())
+            .unwrap_or(false)
+    }
+
+    /// Download git lfs objects on clone and update
+    pub fn resolve_lfs(&self, cache: &Cache) -> bool {
+        self.lfs
+            .or_else(|| self.resolve_from_tags(cache, |tag| tag.lfs).pop())
+            .unwrap_or(false)
+    }
+
+    /// How the repository is updated, `None` if neither the repository or its tags set one
+    pub fn resolve_update_strategy(&self, cache: &Cache) -> Option<UpdateStrategy> {
+        self.update_strategy.or_else(|| {
+            self.resolve_from_tags(cache, |tag| tag.update_strategy)
+                .pop()
+        })
+    }
+
+    /// Stash local changes while updating, `None` if neither the repository or its tags set it
+    pub fn resolve_autostash(&self, cache: &Cache) -> Option<bool> {
+        self.autostash
+            .or_else(|| self.resolve_from_tags(cache, |tag| tag.autostash).pop())
+    }
+
+    /// Credential providers used by libgit2, `None` if neither the repository or its tags set them
+    pub fn resolve_credentials(&self, cache: &Cache) -> Option<Vec<CredentialProvider>> {
+        self.credentials.clone().or_else(|| {
+            self.resolve_from_tags(cache, |tag| tag.credentials.clone())
+                .pop()
+        })
+    }
+
+    /// Commands executed after the repository has been cloned
+    ///
+    /// The repository's own command is first followed by the commands of its tags, ordered by
+    /// priority.
+    pub fn clone_hooks(&self, cache: &Cache) -> Vec<String> {
+        let mut hooks: Vec<String> = self.clone.iter().cloned().collect();
+        hooks.extend(self.resolve_from_tags(cache, |t| t.clone.clone()));
+        hooks
+    }
+
+    /// Commands executed by the work command
+    ///
+    /// The repository's own command is first followed by the commands of its tags, ordered by
+    /// priority.
+    pub fn work_hooks(&self, cache: &Cache) -> Vec<String> {
+        let mut hooks: Vec<String> = self.work.iter().cloned().collect();
+        hooks.extend(self.resolve_from_tags(cache, |t| t.work.clone()));
+        hooks
+    }
+
     pub fn path_from_location(location: Location) -> PathBuf {
         match location {
             Location::Global => Config::global_path().join("repository"),
@@ -80,12 +198,10 @@ impl Repository {
     }
 
     pub fn del_cache_file(&self) -> Result<()> {
-        std::fs::remove_file(&self.config)
-            .context(format!(
-                "failed to remove repository config file: {}",
-                &self.config.display()
-            ))
-            .map_err(Into::into)
+        std::fs::remove_file(&self.config).context(format!(
+            "failed to remove repository config file: {}",
+            &self.config.display()
+        ))
     }
 }
 
@@ -95,9 +211,20 @@ impl RepositoryBuilder {
             name: name.to_owned(),
             remotes: Vec::new(),
             tags: BTreeSet::new(),
+            aliases: BTreeSet::new(),
             location: Location::default(),
             use_cli: None,
+            backend: None,
             path: None,
+            branch: None,
+            depth: None,
+            filter: None,
+            sparse: None,
+            submodules: None,
+            lfs: None,
+            update_strategy: None,
+            autostash: None,
+            credentials: None,
             work: None,
             clone: None,
         }
@@ -113,6 +240,11 @@ impl RepositoryBuilder {
         self
     }
 
+    pub fn alias(mut self, alias: String) -> Self {
+        self.aliases.insert(alias);
+        self
+    }
+
     pub fn location(mut self, location: Location) -> Self {
         self.location = location;
         self
@@ -123,11 +255,61 @@ impl RepositoryBuilder {
         self
     }
 
+    pub fn branch(mut self, branch: String) -> Self {
+        self.branch = Some(branch);
+        self
+    }
+
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    pub fn filter(mut self, filter: String) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn sparse(mut self, path: String) -> Self {
+        self.sparse.get_or_insert_with(Vec::new).push(path);
+        self
+    }
+
+    pub fn submodules(mut self, submodules: bool) -> Self {
+        self.submodules = Some(submodules);
+        self
+    }
+
+    pub fn lfs(mut self, lfs: bool) -> Self {
+        self.lfs = Some(lfs);
+        self
+    }
+
+    pub fn update_strategy(mut self, strategy: UpdateStrategy) -> Self {
+        self.update_strategy = Some(strategy);
+        self
+    }
+
+    pub fn autostash(mut self, autostash: bool) -> Self {
+        self.autostash = Some(autostash);
+        self
+    }
+
+    pub fn credential(mut self, provider: CredentialProvider) -> Self {
+        self.credentials.get_or_insert_with(Vec::new).push(provider);
+        self
+    }
+
     pub fn cli(mut self, use_cli: bool) -> Self {
         self.use_cli = Some(use_cli);
         self
     }
 
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
     pub fn clone(mut self, command: String) -> Self {
         self.clone = Some(command);
         self
@@ -146,10 +328,21 @@ impl RepositoryBuilder {
             name: self.name,
             remotes: self.remotes,
             tags: self.tags,
+            aliases: self.aliases,
             path: self.path,
+            branch: self.branch,
+            depth: self.depth,
+            filter: self.filter,
+            sparse: self.sparse,
+            submodules: self.submodules,
+            lfs: self.lfs,
+            update_strategy: self.update_strategy,
+            autostash: self.autostash,
+            credentials: self.credentials,
             clone: self.clone,
             work: self.work,
             use_cli: self.use_cli,
+            backend: self.backend,
             location: self.location,
             config,
         }
@@ -0,0 +1,169 @@
+//! Resolve a user provided name against a list of known names and aliases.
+
+/// Result of resolving a query against a list of candidates
+#[derive(Debug, Eq, PartialEq)]
+pub enum Resolution<'a> {
+    /// The query identifies a single name, `exact` is set when it is the name or one of its
+    /// aliases rather than a prefix or fuzzy match
+    Found { name: &'a str, exact: bool },
+    /// The query matches more than one name
+    Ambiguous(Vec<&'a str>),
+    /// The query does not match any name, with the closest names as suggestions
+    NotFound(Vec<&'a str>),
+}
+
+/// Resolve a query against a list of `(key, name)` candidates
+///
+/// A key is either a name or an alias of a name. Resolution is attempted in the following order,
+/// stopping at the first step that identifies a single name:
+///
+/// 1. Exact match of a name
+/// 2. Exact match of an alias
+/// 3. Keys that start with the query
+/// 4. Keys that contain the characters of the query in order
+pub fn resolve<'a>(query: &str, candidates: &[(&'a str, &'a str)]) -> Resolution<'a> {
+    if let Some((_, name)) = candidates
+        .iter()
+        .find(|(key, name)| key == name && *key == query)
+    {
+        return Resolution::Found { name, exact: true };
+    }
+
+    let aliases = matching(candidates, |key| key == query);
+    if aliases.len() == 1 {
+        return Resolution::Found {
+            name: aliases[0],
+            exact: true,
+        };
+    }
+
+    let prefixes = matching(candidates, |key| key.starts_with(query));
+    match prefixes.len() {
+        1 => {
+            return Resolution::Found {
+                name: prefixes[0],
+                exact: false,
+            }
+        }
+        0 => {}
+        _ => return Resolution::Ambiguous(prefixes),
+    }
+
+    let fuzzy = matching(candidates, |key| is_subsequence(query, key));
+    match fuzzy.len() {
+        1 => {
+            return Resolution::Found {
+                name: fuzzy[0],
+                exact: false,
+            }
+        }
+        0 => {}
+        _ => return Resolution::Ambiguous(fuzzy),
+    }
+
+    Resolution::NotFound(suggestions(query, candidates))
+}
+
+/// Unique names, in sorted order, of candidates with a key matching the predicate
+fn matching<'a, F>(candidates: &[(&'a str, &'a str)], predicate: F) -> Vec<&'a str>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut names: Vec<&str> = candidates
+        .iter()
+        .filter(|(key, _)| predicate(key))
+        .map(|(_, name)| *name)
+        .collect();
+
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Names of the candidates closest to the query by edit distance
+fn suggestions<'a>(query: &str, candidates: &[(&'a str, &'a str)]) -> Vec<&'a str> {
+    let threshold = (query.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .iter()
+        .map(|(key, name)| (levenshtein(query, key), *name))
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect();
+
+    scored.sort_unstable();
+
+    let mut names = Vec::new();
+    for (_, name) in scored {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    names.truncate(3);
+    names
+}
+
+fn is_subsequence(query: &str, key: &str) -> bool {
+    let mut chars = key.chars();
+    query.chars().all(|q| chars.any(|k| k == q))
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANDIDATES: &[(&str, &str)] = &[
+        ("repo", "repo"),
+        ("rp", "repo"),
+        ("repository", "repository"),
+        ("cargo", "cargo"),
+        ("rustup", "rustup"),
+    ];
+
+    fn found(name: &str, exact: bool) -> Resolution<'_> {
+        Resolution::Found { name, exact }
+    }
+
+    #[test]
+    fn exact_and_alias() {
+        assert_eq!(resolve("repo", CANDIDATES), found("repo", true));
+        assert_eq!(resolve("rp", CANDIDATES), found("repo", true));
+    }
+
+    #[test]
+    fn prefix() {
+        assert_eq!(resolve("car", CANDIDATES), found("cargo", false));
+        assert_eq!(resolve("repos", CANDIDATES), found("repository", false));
+        assert_eq!(
+            resolve("rep", CANDIDATES),
+            Resolution::Ambiguous(vec!["repo", "repository"])
+        );
+    }
+
+    #[test]
+    fn fuzzy() {
+        assert_eq!(resolve("rsup", CANDIDATES), found("rustup", false));
+        assert_eq!(
+            resolve("crago", CANDIDATES),
+            Resolution::NotFound(vec!["cargo"])
+        );
+        assert_eq!(resolve("zzz", CANDIDATES), Resolution::NotFound(vec![]));
+    }
+}
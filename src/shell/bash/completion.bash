@@ -113,6 +113,24 @@ _repo() {
             work)
                 cmd+="__work"
                 ;;
+            status)
+                cmd+="__status"
+                ;;
+            import)
+                cmd+="__import"
+                ;;
+            manifest)
+                cmd+="__manifest"
+                ;;
+            hooks)
+                cmd+="__hooks"
+                ;;
+            cache)
+                cmd+="__cache"
+                ;;
+            prune-branches)
+                cmd+="__prune-branches"
+                ;;
             *)
                 ;;
         esac
@@ -120,7 +138,7 @@ _repo() {
 
     case "${cmd}" in
         repo)
-            opts="-h -V --help --version add config edit foreach init inspect list remove tag update work help"
+            opts="-h -V --help --version add cache config edit foreach hooks import init inspect list manifest prune-branches remove status tag update work help"
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 1 ]] ; then
                 COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
                 return 0
@@ -142,7 +160,7 @@ _repo() {
 
             # Check if the current argument is '--', if so then complete options for add
             case "$cur" in
-                --*) __repo_comp "--local --force --cli --help --version --tag --path --clone --work --remote" ; return 0 ;;
+                --*) __repo_comp "--local --force --cli --help --version --tag --path --branch --depth --filter --sparse --submodules --lfs --update-strategy --autostash --credentials --clone --work --remote --backend" ; return 0 ;;
             esac
             return 0
             ;;
@@ -167,7 +185,7 @@ _repo() {
 
             # Check if the current argument is '--', if so then complete options for add
             case "$cur" in
-                --*) __repo_comp "--local --global --edit --cli --help --version --tag --path --remote" ; return 0 ;;
+                --*) __repo_comp "--local --global --edit --cli --help --version --tag --path --branch --depth --filter --sparse --submodules --lfs --update-strategy --autostash --credentials --remote --backend" ; return 0 ;;
             esac
             __repo_comp "$(__repo_repositories)"
             return 0
@@ -181,7 +199,7 @@ _repo() {
 
             # Check if the current argument is '--', if so then complete options for add
             case "$cur" in
-                --*) __repo_comp "--local --global --all --help --version --tag" ; return 0 ;;
+                --*) __repo_comp "--local --global --all --help --version --tag --keep-going --jobs --group" ; return 0 ;;
             esac
             return 0
             ;;
@@ -263,7 +281,7 @@ _repo() {
         repo__tag__add)
              # Check if the current argument is '--', if so then complete options for add
             case "$cur" in
-                --*) __repo_comp "--local --help --version --path --clone --work" ; return 0 ;;
+                --*) __repo_comp "--local --help --version --path --branch --depth --filter --sparse --submodules --lfs --update-strategy --autostash --credentials --clone --work" ; return 0 ;;
             esac
             return 0
             ;;
@@ -271,7 +289,7 @@ _repo() {
         repo__tag__edit)
              # Check if the current argument is '--', if so then complete options for add
             case "$cur" in
-                --*) __repo_comp "--local --global --edit --help --version --path" ; return 0 ;;
+                --*) __repo_comp "--local --global --edit --help --version --path --branch --depth --filter --sparse --submodules --lfs --update-strategy --autostash --credentials" ; return 0 ;;
             esac
             __repo_comp "$(__repo_tags)"
             return 0
@@ -315,7 +333,55 @@ _repo() {
 
             # Check if the current argument is '--', if so then complete options for add
             case "$cur" in
-                --*) __repo_comp "--local --global --all --help --version --tag" ; return 0 ;;
+                --*) __repo_comp "--local --global --all --help --version --tag --jobs --unshallow --strategy --autostash --all-branches --prune-remotes" ; return 0 ;;
+            esac
+            return 0
+            ;;
+
+        repo__status)
+            # Check if the current argument is '--', if so then complete options for status
+            case "$cur" in
+                --*) __repo_comp "--tags --local --global --all --help --version" ; return 0 ;;
+            esac
+            return 0
+            ;;
+
+        repo__import)
+            # Check if the current argument is '--', if so then complete options for import
+            case "$cur" in
+                --*) __repo_comp "--tags --local --dry-run --help --version" ; return 0 ;;
+            esac
+            return 0
+            ;;
+
+        repo__manifest)
+            # Check if the current argument is '--', if so then complete options for manifest
+            case "$cur" in
+                --*) __repo_comp "--help --version" ; return 0 ;;
+            esac
+            return 0
+            ;;
+
+        repo__hooks)
+            # Check if the current argument is '--', if so then complete options for hooks
+            case "$cur" in
+                --*) __repo_comp "--help --version" ; return 0 ;;
+            esac
+            return 0
+            ;;
+
+        repo__cache)
+            # Check if the current argument is '--', if so then complete options for cache
+            case "$cur" in
+                --*) __repo_comp "--help --version" ; return 0 ;;
+            esac
+            return 0
+            ;;
+
+        repo__prune-branches)
+            # Check if the current argument is '--', if so then complete options for prune-branches
+            case "$cur" in
+                --*) __repo_comp "--tag --local --global --all --dry-run --force --help --version" ; return 0 ;;
             esac
             return 0
             ;;
@@ -1,4 +1,4 @@
-for x in add edit foreach update
+for x in add edit foreach status update
     complete -c repo -x -n "__fish_seen_subcommand_from $x" -s t -l tag -a "(repo tag list)"
 end
 
@@ -19,6 +19,12 @@ complete -c repo -n "__fish_use_subcommand" -f -a "remove" -d 'Remove a reposito
 complete -c repo -n "__fish_use_subcommand" -f -a "tag" -d 'Manage tags'
 complete -c repo -n "__fish_use_subcommand" -f -a "update" -d 'Update tracked repositories in repo with their remotes'
 complete -c repo -n "__fish_use_subcommand" -f -a "work" -d 'Generate work command for a repostory'
+complete -c repo -n "__fish_use_subcommand" -f -a "status" -d 'Show the git state of tracked repositories'
+complete -c repo -n "__fish_use_subcommand" -f -a "import" -d 'Track existing git repositories found in a directory'
+complete -c repo -n "__fish_use_subcommand" -f -a "manifest" -d 'Share a workspace definition as a single manifest file'
+complete -c repo -n "__fish_use_subcommand" -f -a "hooks" -d 'Manage repository hooks'
+complete -c repo -n "__fish_use_subcommand" -f -a "cache" -d 'Manage the mirror cache that clones borrow objects from'
+complete -c repo -n "__fish_use_subcommand" -f -a "prune-branches" -d 'Delete local branches whose upstream no longer exists'
 complete -c repo -n "__fish_use_subcommand" -f -a "help" -d 'Prints this message or the help of the given subcommand(s)'
 
 complete -c repo -n "__fish_seen_subcommand_from add" -s t -l tag -d 'Add a tag to repository'
@@ -29,6 +35,16 @@ complete -c repo -n "__fish_seen_subcommand_from add" -s r -l remote -d 'Add an
 complete -c repo -n "__fish_seen_subcommand_from add" -s l -l local -d 'Write repository to local cache'
 complete -c repo -n "__fish_seen_subcommand_from add" -s f -l force -d 'Override repository if it is already tracked by repo'
 complete -c repo -n "__fish_seen_subcommand_from add" -s u -l cli -d 'Flag repository to interact with git through the command line'
+complete -c repo -n "__fish_seen_subcommand_from add" -l backend -xa 'libgit cli auto' -d 'Backend used to interact with git'
+complete -c repo -n "__fish_seen_subcommand_from add" -s b -l branch -x -d 'Branch to check out when the repository is cloned'
+complete -c repo -n "__fish_seen_subcommand_from add" -l depth -x -d 'Number of commits to fetch when the repository is cloned'
+complete -c repo -n "__fish_seen_subcommand_from add" -l filter -x -d 'Partial clone filter used when the repository is cloned'
+complete -c repo -n "__fish_seen_subcommand_from add" -l sparse -x -d 'Path to check out with a sparse checkout, can be repeated'
+complete -c repo -n "__fish_seen_subcommand_from add" -l submodules -d 'Initialize and update submodules recursively on clone and update'
+complete -c repo -n "__fish_seen_subcommand_from add" -l lfs -d 'Download git lfs objects on clone and update'
+complete -c repo -n "__fish_seen_subcommand_from add" -l update-strategy -r -f -a 'fetch ff-only rebase reset-hard' -d 'How the repository is brought up to date on update'
+complete -c repo -n "__fish_seen_subcommand_from add" -l autostash -d 'Stash local changes while updating the branch'
+complete -c repo -n "__fish_seen_subcommand_from add" -l credentials -r -f -a 'ssh-agent ssh-key helper token default' -d 'Credential providers libgit2 tries in order'
 
 complete -c repo -n "__fish_seen_subcommand_from config" -s l -l local -d 'Interact with local config.'
 complete -c repo -n "__fish_seen_subcommand_from config" -s g -l global -d 'Interact with global config'
@@ -44,11 +60,24 @@ complete -c repo -n "__fish_seen_subcommand_from edit" -s l -l local -d 'Change
 complete -c repo -n "__fish_seen_subcommand_from edit" -s g -l global -d 'Change repository to be a stored in the global cache'
 complete -c repo -n "__fish_seen_subcommand_from edit" -s e -l edit -d 'Open cache file in $EDITOR'
 complete -c repo -n "__fish_seen_subcommand_from edit" -s u -l cli -d 'Flag repository to interact with git through the command line'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l backend -xa 'libgit cli auto' -d 'Backend used to interact with git'
+complete -c repo -n "__fish_seen_subcommand_from edit" -s b -l branch -x -d 'Branch to check out when the repository is cloned'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l depth -x -d 'Number of commits to fetch when the repository is cloned'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l filter -x -d 'Partial clone filter used when the repository is cloned'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l sparse -x -d 'Path to check out with a sparse checkout, can be repeated'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l submodules -d 'Initialize and update submodules recursively on clone and update'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l lfs -d 'Download git lfs objects on clone and update'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l update-strategy -r -f -a 'fetch ff-only rebase reset-hard' -d 'How the repository is brought up to date on update'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l autostash -d 'Stash local changes while updating the branch'
+complete -c repo -n "__fish_seen_subcommand_from edit" -l credentials -r -f -a 'ssh-agent ssh-key helper token default' -d 'Credential providers libgit2 tries in order'
 
 complete -c repo -n "__fish_seen_subcommand_from foreach" -s t -l tag -d 'Perform operation on only repositories that contain tag'
 complete -c repo -n "__fish_seen_subcommand_from foreach" -s l -l local -d 'Perform operation on only local repositories'
 complete -c repo -n "__fish_seen_subcommand_from foreach" -s g -l global -d 'Perform operation on only global repositories'
 complete -c repo -n "__fish_seen_subcommand_from foreach" -s a -l all -d 'Perform operation on all repositories, global and local'
+complete -c repo -n "__fish_seen_subcommand_from foreach" -s k -l keep-going -d 'Continue with the remaining repositories when a command fails'
+complete -c repo -n "__fish_seen_subcommand_from foreach" -s j -l jobs -x -d 'Number of repositories to execute the command in concurrently'
+complete -c repo -n "__fish_seen_subcommand_from foreach" -s G -l group -d 'Group the output of each repository'
 
 complete -c repo -n "__fish_seen_subcommand_from inspect" -s f -l format -d 'Define the output format of the inspection'
 complete -c repo -n "__fish_seen_subcommand_from inspect" -x -a "bash ron toml"
@@ -84,7 +113,30 @@ complete -c repo -n "__fish_seen_subcommand_from update" -s t -l tag -d 'Perform
 complete -c repo -n "__fish_seen_subcommand_from update" -s l -l local -d 'Perform operation on only local repositories'
 complete -c repo -n "__fish_seen_subcommand_from update" -s g -l global -d 'Perform operation on only global repositories'
 complete -c repo -n "__fish_seen_subcommand_from update" -s a -l all -d 'Perform operation on all repositories, global and local'
+complete -c repo -n "__fish_seen_subcommand_from update" -s j -l jobs -x -d 'Number of repositories to update concurrently'
+complete -c repo -n "__fish_seen_subcommand_from update" -l unshallow -d 'Fetch the complete history of shallow clones'
+complete -c repo -n "__fish_seen_subcommand_from update" -l strategy -r -f -a 'fetch ff-only rebase reset-hard' -d 'Update strategy used for this run'
+complete -c repo -n "__fish_seen_subcommand_from update" -l autostash -d 'Stash local changes while updating the branch'
+complete -c repo -n "__fish_seen_subcommand_from update" -l all-branches -d 'Fast-forward every local branch that tracks an upstream'
+complete -c repo -n "__fish_seen_subcommand_from update" -l prune-remotes -d 'Remove git remotes that are not listed in the repository'
 
 complete -c repo -n "__fish_seen_subcommand_from work" -s q -l quick -d 'Only change directory to repository in workspace'
+complete -c repo -n "__fish_seen_subcommand_from work" -s l -l list -d 'Print the resolved work hooks without executing them'
 complete -c repo -n "__fish_seen_subcommand_from help" -s h -l help -d 'Prints help information'
 complete -c repo -n "__fish_seen_subcommand_from help" -s V -l version -d 'Prints version information'
+
+complete -c repo -n "__fish_seen_subcommand_from status" -s t -l tags -d 'Show repositories that contain a tag'
+complete -c repo -n "__fish_seen_subcommand_from status" -s l -l local -d 'Show only local repositories'
+complete -c repo -n "__fish_seen_subcommand_from status" -s g -l global -d 'Show only global repositories'
+complete -c repo -n "__fish_seen_subcommand_from status" -s a -l all -d 'Show all repositories regardless of config filters'
+
+complete -c repo -n "__fish_seen_subcommand_from import" -s t -l tags -d 'Add a tag to every imported repository'
+complete -c repo -n "__fish_seen_subcommand_from import" -s l -l local -d 'Write repositories to local cache'
+complete -c repo -n "__fish_seen_subcommand_from import" -s n -l dry-run -d 'Only print the repositories that would be imported'
+
+complete -c repo -n "__fish_seen_subcommand_from prune-branches" -s t -l tag -d 'Perform operation on only repositories that contain tag'
+complete -c repo -n "__fish_seen_subcommand_from prune-branches" -s l -l local -d 'Perform operation on only local repositories'
+complete -c repo -n "__fish_seen_subcommand_from prune-branches" -s g -l global -d 'Perform operation on only global repositories'
+complete -c repo -n "__fish_seen_subcommand_from prune-branches" -s a -l all -d 'Perform operation on all repositories, global and local'
+complete -c repo -n "__fish_seen_subcommand_from prune-branches" -s n -l dry-run -d 'Show the branches that would be deleted without deleting them'
+complete -c repo -n "__fish_seen_subcommand_from prune-branches" -s f -l force -d 'Delete the branches without a confirmation prompt'
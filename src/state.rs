@@ -0,0 +1,87 @@
+use crate::{config::Config, util, Backend};
+use crate::{error::Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{io::Write, path::PathBuf};
+
+/// Completion state of a repository hook
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookStatus {
+    /// Hooks have started but have not finished, e.g. the process was interrupted
+    Pending,
+    /// Hooks have finished successfully
+    Complete,
+    /// One of the hooks has failed
+    Failed,
+}
+
+/// Machine specific state of a repository in the workspace
+///
+/// State is stored in the local cache as it describes the workspace on this machine and should not
+/// be shared with the repository definition.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct State {
+    /// Status of the clone hooks. `None` if the repository was cloned before state was recorded
+    pub clone_hooks: Option<HookStatus>,
+
+    /// Backend that worked the last time the repository was updated with the `auto` backend
+    pub backend: Option<Backend>,
+
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl State {
+    pub fn path_from_name(name: &str) -> PathBuf {
+        Config::local_path()
+            .join("state")
+            .join(format!("{}.toml", name))
+    }
+
+    /// Load the state of a repository, returning the default state if none has been recorded
+    pub fn load(name: &str) -> Result<Self> {
+        let path = State::path_from_name(name);
+        let mut state: State = if path.is_file() {
+            let content = util::read_content(&path)?;
+            toml::from_str(&content).context(format!(
+                "could not serialize content into State:\n\n{}",
+                content
+            ))?
+        } else {
+            State::default()
+        };
+
+        state.path = path;
+        Ok(state)
+    }
+
+    /// Check if the clone hooks did not finish and should be executed again
+    pub fn clone_hooks_incomplete(&self) -> bool {
+        matches!(
+            self.clone_hooks,
+            Some(HookStatus::Pending) | Some(HookStatus::Failed)
+        )
+    }
+
+    pub fn write(&self) -> Result<()> {
+        debug!("Writing state to: {:#?}", self.path);
+
+        let ser = toml::to_string_pretty(&self)
+            .context(format!("failed to serialize state to file\n\n{:#?}", self))?;
+
+        util::write_content(&self.path, |f| {
+            f.write_fmt(format_args!("{}", ser))
+                .context(format!("failed to write file: {:#?}", self.path))
+        })
+    }
+
+    pub fn remove(name: &str) -> Result<()> {
+        let path = State::path_from_name(name);
+        if path.is_file() {
+            std::fs::remove_file(&path)
+                .context(format!("failed to remove state file: {}", path.display()))?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,21 @@
-use crate::{config::Config, Location};
-use anyhow::{Context, Result};
+use crate::{config::Config, CredentialProvider, Location, UpdateStrategy};
+use crate::{error::Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
 pub struct Tag {
     pub name: String,
     pub path: Option<PathBuf>,
+    pub branch: Option<String>,
+    pub depth: Option<u32>,
+    pub filter: Option<String>,
+    pub sparse: Option<Vec<String>>,
+    pub submodules: Option<bool>,
+    pub lfs: Option<bool>,
+    pub update_strategy: Option<UpdateStrategy>,
+    pub autostash: Option<bool>,
+    pub credentials: Option<Vec<CredentialProvider>>,
     pub clone: Option<String>,
     pub work: Option<String>,
     pub priority: Option<i32>,
@@ -22,6 +31,15 @@ pub struct TagBuilder {
     name: String,
     location: Location,
     path: Option<PathBuf>,
+    branch: Option<String>,
+    depth: Option<u32>,
+    filter: Option<String>,
+    sparse: Option<Vec<String>>,
+    submodules: Option<bool>,
+    lfs: Option<bool>,
+    update_strategy: Option<UpdateStrategy>,
+    autostash: Option<bool>,
+    credentials: Option<Vec<CredentialProvider>>,
     work: Option<String>,
     clone: Option<String>,
     priority: Option<i32>,
@@ -45,12 +63,10 @@ impl Tag {
     }
 
     pub fn del_cache_file(&self) -> Result<()> {
-        std::fs::remove_file(&self.config)
-            .context(format!(
-                "failed to remove tag config file: {}",
-                &self.config.display()
-            ))
-            .map_err(Into::into)
+        std::fs::remove_file(&self.config).context(format!(
+            "failed to remove tag config file: {}",
+            &self.config.display()
+        ))
     }
 }
 
@@ -60,6 +76,15 @@ impl TagBuilder {
             name: name.to_owned(),
             location: Location::default(),
             path: None,
+            branch: None,
+            depth: None,
+            filter: None,
+            sparse: None,
+            submodules: None,
+            lfs: None,
+            update_strategy: None,
+            autostash: None,
+            credentials: None,
             clone: None,
             work: None,
             priority: None,
@@ -76,6 +101,51 @@ impl TagBuilder {
         self
     }
 
+    pub fn branch(mut self, branch: String) -> Self {
+        self.branch = Some(branch);
+        self
+    }
+
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    pub fn filter(mut self, filter: String) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn sparse(mut self, path: String) -> Self {
+        self.sparse.get_or_insert_with(Vec::new).push(path);
+        self
+    }
+
+    pub fn submodules(mut self, submodules: bool) -> Self {
+        self.submodules = Some(submodules);
+        self
+    }
+
+    pub fn lfs(mut self, lfs: bool) -> Self {
+        self.lfs = Some(lfs);
+        self
+    }
+
+    pub fn update_strategy(mut self, strategy: UpdateStrategy) -> Self {
+        self.update_strategy = Some(strategy);
+        self
+    }
+
+    pub fn autostash(mut self, autostash: bool) -> Self {
+        self.autostash = Some(autostash);
+        self
+    }
+
+    pub fn credential(mut self, provider: CredentialProvider) -> Self {
+        self.credentials.get_or_insert_with(Vec::new).push(provider);
+        self
+    }
+
     pub fn clone(mut self, command: String) -> Self {
         self.clone = Some(command);
         self
@@ -98,6 +168,15 @@ impl TagBuilder {
             name: self.name,
             location: self.location,
             path: self.path,
+            branch: self.branch,
+            depth: self.depth,
+            filter: self.filter,
+            sparse: self.sparse,
+            submodules: self.submodules,
+            lfs: self.lfs,
+            update_strategy: self.update_strategy,
+            autostash: self.autostash,
+            credentials: self.credentials,
             clone: self.clone,
             work: self.work,
             priority: self.priority,
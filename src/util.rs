@@ -1,15 +1,19 @@
-use anyhow::{Context, Result};
+use crate::{error::Context, Error, Result};
 use std::{
     borrow::Borrow,
     fs::{File, OpenOptions},
     io::Read,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
 };
 
 pub fn make_path_buf<S: AsRef<str>>(s: S) -> Result<PathBuf> {
     shellexpand::full(s.as_ref())
         .map(|s| PathBuf::from(s.borrow() as &str))
-        .map_err(Into::into)
+        .map_err(|e| Error::InvalidValue(format!("failed to expand path: {}", e)))
 }
 
 pub fn read_content<P>(path: P) -> Result<String>
@@ -39,11 +43,49 @@ where
     write_fn(&mut file)
 }
 
+/// Apply `f` to every item using at most `jobs` worker threads.
+///
+/// Items are handed out to workers as they become free and the results are returned in the same
+/// order as the input.
+pub fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let item = match items.get(index) {
+                    Some(item) => item,
+                    None => break,
+                };
+
+                let result = f(item);
+                results.lock().expect("worker result lock poisoned")[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .expect("worker result lock poisoned")
+        .into_iter()
+        .map(|r| r.expect("worker did not produce a result"))
+        .collect()
+}
+
 pub mod process {
-    use anyhow::{Context, Result};
+    use crate::{error::Context, Result};
     use std::{
         io::{BufRead, BufReader},
         process::{Command, ExitStatus, Stdio},
+        sync::Mutex,
     };
 
     pub fn inherit(name: &str) -> Command {
@@ -71,33 +113,64 @@ pub mod process {
     }
 
     pub fn execute_command(command: &mut Command, prefix: String) -> Result<ExitStatus> {
+        execute_with(command, &prefix, |line| print!("{}", line))
+    }
+
+    /// Execute a command, collecting its prefixed output instead of printing it.
+    ///
+    /// This is used when multiple commands are running at the same time and the output of each
+    /// command should be printed as a single block once it has finished.
+    pub fn execute_command_grouped(
+        command: &mut Command,
+        prefix: String,
+    ) -> Result<(ExitStatus, String)> {
+        let output = Mutex::new(String::new());
+        let status = execute_with(command, &prefix, |line| {
+            output
+                .lock()
+                .expect("command output lock poisoned")
+                .push_str(&line)
+        })?;
+
+        let output = output.into_inner().expect("command output lock poisoned");
+        Ok((status, output))
+    }
+
+    fn execute_with<F>(command: &mut Command, prefix: &str, write: F) -> Result<ExitStatus>
+    where
+        F: Fn(String) + Sync,
+    {
         let mut child = command
             .spawn()
             .context("failed executing command as a child process")?;
 
-        let stdout_child = if let Some(stdout) = child.stdout.take() {
-            let pre = prefix.clone();
-            Some(std::thread::spawn(move || forward_stdout(stdout, &pre)))
-        } else {
-            None
-        };
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        std::thread::scope(|scope| -> Result<()> {
+            let stdout_child =
+                stdout.map(|stdout| scope.spawn(|| forward_stdout(stdout, prefix, &write)));
 
-        if let Some(stderr) = child.stderr.take() {
-            forward_stdout(stderr, &prefix).context("could not forward stderr to stdout")?;
-        }
+            if let Some(stderr) = stderr {
+                forward_stdout(stderr, prefix, &write)
+                    .context("could not forward stderr to stdout")?;
+            }
 
-        if let Some(child_thread) = stdout_child {
-            child_thread
-                .join()
-                .expect("failed to join stdout child thread with main thread")?;
-        }
+            if let Some(child_thread) = stdout_child {
+                child_thread
+                    .join()
+                    .expect("failed to join stdout child thread with main thread")?;
+            }
+
+            Ok(())
+        })?;
 
         child.wait().map_err(Into::into)
     }
 
-    fn forward_stdout<T>(read: T, prefix: &str) -> Result<()>
+    fn forward_stdout<T, F>(read: T, prefix: &str, write: &F) -> Result<()>
     where
         T: std::io::Read,
+        F: Fn(String),
     {
         let mut buffer = BufReader::new(read);
         loop {
@@ -109,11 +182,15 @@ pub mod process {
                 break;
             }
 
+            if !line.ends_with('\n') {
+                line.push('\n');
+            }
+
             // TODO: Have computed the larget string before calling this
             // but format does not allow formatting with dynamic variables.
             // This means that I can't format left based on the max_size
             let prefix = format!("{:>20.20} |", prefix);
-            print!("{} {}", prefix, line);
+            write(format!("{} {}", prefix, line));
         }
 
         Ok(())
@@ -137,3 +214,24 @@ pub fn canonicalize<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
         })
         .map(PathBuf::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_map_keeps_order() {
+        let items: Vec<usize> = (0..50).collect();
+        let result = parallel_map(&items, 4, |i| i * 2);
+
+        assert_eq!(result, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parallel_map_empty() {
+        let items: Vec<usize> = Vec::new();
+        let result = parallel_map(&items, 0, |i| *i);
+
+        assert!(result.is_empty());
+    }
+}
@@ -1,6 +1,69 @@
-use crate::{config::Config, git, util, Cache, Location, Repository, Tag};
-use anyhow::{anyhow, Context, Result};
-use std::{collections::HashMap, io::Write};
+use crate::{
+    config::Config,
+    git,
+    mirror::Mirror,
+    progress,
+    resolve::{self, Resolution},
+    util, Backend, Cache, Change, HookStatus, Location, Plan, Query, Remote, Repository,
+    RepositoryBuilder, State, Status, Tag, UpdateStrategy,
+};
+use crate::{error::Context, Error, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Outcome of synchronizing a repository with its remotes
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UpdateStatus {
+    /// Repository did not exist in the workspace and has been cloned
+    Cloned,
+    /// Repository existed in the workspace and has been merged with its upstream
+    Updated {
+        /// Local branches whose upstream is gone
+        gone: Vec<String>,
+        /// Changes made to the git remotes of the clone to match the repository's remotes
+        remotes: Vec<git::RemoteChange>,
+    },
+    /// Repository was not touched, with the reason why
+    Skipped(String),
+}
+
+/// Local branches of a clone whose upstream no longer exists on the remote
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct GoneBranches {
+    /// Branches merged into the checked out branch that can be deleted
+    pub merged: Vec<String>,
+    /// Branches with commits that only exist on the branch, they are never deleted
+    pub unmerged: Vec<String>,
+}
+
+/// Outcome of removing the mirrors that are no longer used
+#[derive(Debug, Default)]
+pub struct MirrorGc {
+    /// Mirrors that have been removed, or would be removed on a dry run
+    pub removed: Vec<PathBuf>,
+    /// Tracked clones that failed to stop borrowing from unused mirrors, the mirrors are kept
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+/// Options that change how repositories are synchronized with their remotes
+#[derive(Debug, Default, Clone)]
+pub struct UpdateOptions {
+    /// Fetch the complete history of shallow clones and clone new repositories in full
+    pub unshallow: bool,
+    /// Update strategy used instead of the one set by the repository, its tags or the config
+    pub strategy: Option<UpdateStrategy>,
+    /// Stash local changes while updating the branch even if the repository or config does not
+    /// enable it
+    pub autostash: bool,
+    /// Prune remote-tracking branches and fast-forward every local branch that tracks an upstream
+    pub all_branches: bool,
+    /// Remove the git remotes of a clone that are not in the repository's remotes
+    pub prune_remotes: bool,
+}
 
 #[derive(Debug)]
 pub struct Workspace {
@@ -12,8 +75,14 @@ impl Workspace {
     pub fn new() -> Result<Self> {
         let config = Config::new()?;
         let cache = Cache::new()?;
+        let workspace = Self { config, cache };
 
-        Ok(Self { config, cache })
+        // NOTE: The libgit2 timeouts are global, every repository shares the same config
+        if let Some(timeout) = workspace.network().connect_timeout {
+            git::libgit::set_server_timeouts(timeout);
+        }
+
+        Ok(workspace)
     }
 
     pub fn cache(&self) -> &Cache {
@@ -54,12 +123,10 @@ impl Workspace {
     /// Adding a repository to the cache will also write the repository to disk
     pub fn add_repository(&mut self, repository: Repository) -> Result<()> {
         if self.has_repository(&repository.name) {
-            return Err(anyhow!(
-                "Repository: {} already exist in repo",
-                repository.name
-            ));
+            return Err(Error::RepositoryExists(repository.name));
         }
 
+        self.check_aliases(&repository)?;
         self.write_repository(&repository)?;
         self.cache.add_repository(repository);
 
@@ -71,7 +138,7 @@ impl Workspace {
     /// Adding a tag to the cache will also write the tag to disk
     pub fn add_tag(&mut self, tag: Tag) -> Result<()> {
         if self.has_tag(&tag.name) {
-            return Err(anyhow!("Tag: {} already exist in repo", tag.name));
+            return Err(Error::TagExists(tag.name));
         }
 
         self.write_tag(&tag)?;
@@ -90,6 +157,87 @@ impl Workspace {
         None
     }
 
+    /// Find a repository by name, alias, unique prefix or fuzzy match
+    ///
+    /// Only repositories that pass the configuration filters are considered. If no repository
+    /// matches, the error suggests the closest names. The returned flag is set when the query is
+    /// the name or an alias of the repository, commands that change a repository should confirm
+    /// any other match first.
+    pub fn resolve_repository(&self, query: &str) -> Result<(&Repository, bool)> {
+        let repositories = self.repositories();
+        let mut candidates = Vec::new();
+        for repository in &repositories {
+            candidates.push((repository.name.as_str(), repository.name.as_str()));
+            for alias in &repository.aliases {
+                candidates.push((alias.as_str(), repository.name.as_str()));
+            }
+        }
+
+        match resolve::resolve(query, &candidates) {
+            Resolution::Found { name, exact } => self
+                .get_repository(name)
+                .map(|repository| (repository, exact))
+                .ok_or_else(|| Error::RepositoryNotFound {
+                    name: query.to_owned(),
+                    suggestions: Vec::new(),
+                }),
+            Resolution::Ambiguous(names) => Err(Error::RepositoryAmbiguous {
+                name: query.to_owned(),
+                matches: names.into_iter().map(str::to_owned).collect(),
+            }),
+            Resolution::NotFound(names) => Err(Error::RepositoryNotFound {
+                name: query.to_owned(),
+                suggestions: names.into_iter().map(str::to_owned).collect(),
+            }),
+        }
+    }
+
+    /// Find a tag by name, unique prefix or fuzzy match
+    ///
+    /// The returned flag is set when the query is the name of the tag.
+    pub fn resolve_tag(&self, query: &str) -> Result<(&Tag, bool)> {
+        let tags = self.cache.tags();
+        let candidates: Vec<(&str, &str)> = tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.name.as_str()))
+            .collect();
+
+        match resolve::resolve(query, &candidates) {
+            Resolution::Found { name, exact } => self
+                .cache
+                .get_tag(name)
+                .map(|tag| (tag, exact))
+                .ok_or_else(|| Error::TagNotFound {
+                    name: query.to_owned(),
+                    suggestions: Vec::new(),
+                }),
+            Resolution::Ambiguous(names) => Err(Error::TagAmbiguous {
+                name: query.to_owned(),
+                matches: names.into_iter().map(str::to_owned).collect(),
+            }),
+            Resolution::NotFound(names) => Err(Error::TagNotFound {
+                name: query.to_owned(),
+                suggestions: names.into_iter().map(str::to_owned).collect(),
+            }),
+        }
+    }
+
+    /// Check that none of the aliases of a repository is the name of another repository
+    ///
+    /// Names are resolved before aliases, such an alias could never be used.
+    pub fn check_aliases(&self, repository: &Repository) -> Result<()> {
+        for alias in &repository.aliases {
+            if *alias != repository.name && self.has_repository(alias) {
+                return Err(Error::InvalidValue(format!(
+                    "alias '{}' of '{}' is already the name of a repository",
+                    alias, repository.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn take_repository(&mut self, name: &str) -> Option<Repository> {
         if let Some(repo) = self.cache.take_repository(name) {
             if self.filter_repository(&repo) {
@@ -118,7 +266,8 @@ impl Workspace {
 
     pub fn remove_repository(&mut self, name: &str) -> Result<()> {
         debug!("Removing repository: '{}' from cache", name);
-        self.cache.remove_repository(name)
+        self.cache.remove_repository(name)?;
+        State::remove(name)
     }
 
     pub fn remove_tag(&mut self, name: &str) -> Result<()> {
@@ -126,77 +275,623 @@ impl Workspace {
         self.cache.remove_tag(name)
     }
 
-    pub fn update_remotes(&self, repository: &Repository) -> Result<()> {
+    pub fn update_remotes(
+        &self,
+        repository: &Repository,
+        options: &UpdateOptions,
+    ) -> Result<UpdateStatus> {
+        let _progress = progress::Scope::new(&repository.name);
+        let _credentials = git::credentials::Scope::new(self.credentials(repository));
+        let _network = git::network::Scope::new(self.network());
+
+        let use_cli = self.use_cli(repository)?;
+        if !use_cli
+            && self.explicit_backend(repository) == Some(Backend::Libgit)
+            && self.timeout_needs_cli(repository)
+        {
+            warn!(
+                "{}: the timeout cannot be enforced for ssh remotes with the libgit backend",
+                repository.name
+            );
+        }
+
+        if use_cli || self.backend(repository) != Backend::Auto {
+            return self.update_with(repository, options, use_cli);
+        }
+
+        match self.update_with(repository, options, false) {
+            Err(e) if git::should_fall_back(&e) => {
+                warn!(
+                    "{}: libgit2 failed, retrying with git cli: {:#}",
+                    repository.name, e
+                );
+
+                let status = self.update_with(repository, options, true)?;
+                self.record_backend(repository, Backend::Cli)?;
+                Ok(status)
+            }
+            Ok(status) => {
+                self.record_backend(repository, Backend::Libgit)?;
+                Ok(status)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Backend of a repository, the repository's backend or `cli` flag takes precedence over the
+    /// config
+    pub fn backend(&self, repository: &Repository) -> Backend {
+        repository
+            .backend
+            .or_else(|| repository.use_cli.map(Backend::from_cli))
+            .unwrap_or_else(|| self.config.backend(None))
+    }
+
+    /// Backend set for a repository or in the config, `None` if it follows the `cli` option
+    fn explicit_backend(&self, repository: &Repository) -> Option<Backend> {
+        match (repository.backend, repository.use_cli) {
+            (Some(backend), _) => Some(backend),
+            (None, Some(_)) => None,
+            (None, None) => self.config.backend_setting(),
+        }
+    }
+
+    /// Check if the git cli is used for a repository
+    ///
+    /// With the `auto` backend this is the backend that worked for the last update, or the git cli
+    /// for repositories with ssh remotes while a timeout is set as libgit2 cannot abort a stalled
+    /// ssh connection.
+    fn use_cli(&self, repository: &Repository) -> Result<bool> {
+        match self.backend(repository) {
+            Backend::Libgit => Ok(false),
+            Backend::Cli => Ok(true),
+            Backend::Auto if self.timeout_needs_cli(repository) => {
+                info!(
+                    "{}: using git cli for ssh remotes so that the timeout is enforced",
+                    repository.name
+                );
+                Ok(true)
+            }
+            Backend::Auto => Ok(State::load(&repository.name)?.backend == Some(Backend::Cli)),
+        }
+    }
+
+    /// Check if a timeout is set that libgit2 cannot enforce for the remotes of a repository
+    fn timeout_needs_cli(&self, repository: &Repository) -> bool {
+        self.network().timeout.is_some()
+            && repository
+                .remotes
+                .iter()
+                .any(|remote| !git::can_time_out(&remote.url))
+    }
+
+    fn record_backend(&self, repository: &Repository, backend: Backend) -> Result<()> {
+        let mut state = State::load(&repository.name)?;
+        if state.backend != Some(backend) {
+            state.backend = Some(backend);
+            state.write()?;
+        }
+
+        Ok(())
+    }
+
+    fn update_with(
+        &self,
+        repository: &Repository,
+        options: &UpdateOptions,
+        use_cli: bool,
+    ) -> Result<UpdateStatus> {
         let workspace_path = self
             .config
             .root(None)
             .join(repository.resolve_workspace_path(self.cache()));
 
-        let use_cli = repository.use_cli.unwrap_or_else(|| self.config.cli(None));
+        let mut gone = Vec::new();
+        let mut remotes = Vec::new();
+        let was_cloned = if workspace_path.is_dir() {
+            let strategy = options
+                .strategy
+                .or_else(|| repository.resolve_update_strategy(self.cache()))
+                .unwrap_or_else(|| self.config.update_strategy(None));
+            let autostash = options.autostash
+                || repository
+                    .resolve_autostash(self.cache())
+                    .unwrap_or_else(|| self.config.autostash(None));
+
+            let status = git::status(&workspace_path, use_cli)?;
+            if let Some(reason) = status.update_blocker(strategy, autostash) {
+                return Ok(UpdateStatus::Skipped(reason));
+            }
 
-        let mut was_cloned = false;
-        if workspace_path.is_dir() {
-            git::merge(&workspace_path, use_cli)?;
+            // NOTE: A repository without remotes is left alone instead of losing all of its remotes
+            if !repository.remotes.is_empty() {
+                remotes = git::sync_remotes(
+                    &workspace_path,
+                    &repository.remotes,
+                    options.prune_remotes,
+                    use_cli,
+                )?;
+                for change in &remotes {
+                    info!("{}: {}", repository.name, change);
+                }
+            }
+
+            if options.unshallow {
+                git::unshallow(&workspace_path, use_cli)?;
+            }
+
+            let pull = git::PullOptions {
+                strategy,
+                autostash,
+                all_branches: options.all_branches,
+            };
+            let report = git::update(&workspace_path, &pull, use_cli)?;
+            if !report.updated.is_empty() {
+                debug!(
+                    "{}: fast-forwarded branches: {}",
+                    repository.name,
+                    report.updated.join(", ")
+                );
+            }
+
+            gone = report.gone;
+            false
         } else {
-            let remote_name =
-                repository.remotes.first().map(|r| &r.name).ok_or_else(|| {
-                    anyhow!("Repository: {} does not have a remote", repository.name)
-                })?;
+            if repository.remotes.is_empty() {
+                return Ok(UpdateStatus::Skipped("no remote defined".to_owned()));
+            }
 
-            let branch = format!("{}/master", remote_name);
+            let filter = repository.resolve_filter(self.cache());
+            let sparse = repository.resolve_sparse(self.cache());
 
+            // NOTE: libgit2 cannot create partial clones, the git cli is used instead unless the
+            // libgit backend is set explicitly
+            let partial = filter.is_some() || !sparse.is_empty();
+            if partial && !use_cli && self.explicit_backend(repository) == Some(Backend::Libgit) {
+                return Err(Error::InvalidValue(format!(
+                    "Repository: '{}' uses a partial clone or sparse checkout that the libgit backend does not support, use the cli or auto backend",
+                    repository.name
+                )));
+            }
+
+            let options = git::CloneOptions {
+                branch: repository.resolve_branch(self.cache()),
+                references: self.update_mirrors(repository, use_cli),
+                depth: repository
+                    .resolve_depth(self.cache())
+                    .filter(|_| !options.unshallow),
+                filter,
+                sparse,
+            };
             git::clone(
                 &workspace_path,
-                &branch,
                 repository.remotes.as_slice(),
+                &options,
                 use_cli,
             )?;
 
-            was_cloned = true;
+            // NOTE: Mark the clone hooks as pending so they are executed on the next update if
+            // anything after the clone fails
+            let mut state = State::load(&repository.name)?;
+            state.clone_hooks = Some(HookStatus::Pending);
+            state.write()?;
+
+            true
+        };
+
+        if repository.resolve_submodules(self.cache()) {
+            let failed: Vec<String> = git::update_submodules(&workspace_path, use_cli)?
+                .into_iter()
+                .filter_map(|(name, result)| result.err().map(|e| format!("{}: {:#}", name, e)))
+                .collect();
+
+            if !failed.is_empty() {
+                return Err(Error::SubmodulesFailed(failed));
+            }
+        }
+
+        if repository.resolve_lfs(self.cache()) {
+            git::lfs_pull(&workspace_path)?;
+        }
+
+        // NOTE: If the clone hooks failed on a previous update the repository exists in the
+        // workspace but was never fully provisioned. Run them again until they succeed.
+        if was_cloned || State::load(&repository.name)?.clone_hooks_incomplete() {
+            self.run_clone_hooks(repository)?;
         }
 
         if was_cloned {
-            trace!("path: {:#?}", workspace_path.display());
-            let mut after = Vec::new();
-            if let Some(clone) = &repository.clone {
-                after.push(clone.clone());
-            }
-            after
-                .extend_from_slice(&repository.resolve_from_tags(&self.cache, |t| t.clone.clone()));
-
-            let shell = self.config().shell(None);
-            let program = shell.first().ok_or_else(|| {
-                anyhow!("'shell' option in configuration must have at least one field")
-            })?;
-            let rest: &[&str] = shell.split_at(1).1;
-
-            // execute command
-            trace!("Executing commands: {:#?}", after);
-            for cmd in after {
-                if cmd.is_empty() {
-                    return Err(anyhow!("after clone command is empty"));
+            Ok(UpdateStatus::Cloned)
+        } else {
+            Ok(UpdateStatus::Updated { gone, remotes })
+        }
+    }
+
+    /// Credentials libgit2 uses for the remotes of a repository
+    ///
+    /// The providers of the repository or its tags take precedence over the config. Keys and
+    /// tokens come from the host aliases of the config.
+    pub fn credentials(&self, repository: &Repository) -> git::Credentials {
+        let providers = repository
+            .resolve_credentials(self.cache())
+            .unwrap_or_else(|| self.config.credentials(None).to_vec());
+
+        let hosts = self
+            .config
+            .host_aliases(None)
+            .into_iter()
+            .map(|(_, alias)| git::HostCredentials {
+                host: alias.host.clone(),
+                ssh_key: alias
+                    .ssh_key
+                    .as_ref()
+                    .and_then(|key| util::make_path_buf(key).ok()),
+                token_env: alias.token_env.clone(),
+            })
+            .collect();
+
+        git::Credentials { providers, hosts }
+    }
+
+    /// Timeouts and retries of the git operations that talk to a remote
+    pub fn network(&self) -> git::Network {
+        let seconds = |s| Some(Duration::from_secs(s)).filter(|d| !d.is_zero());
+
+        git::Network {
+            connect_timeout: seconds(self.config.connect_timeout(None)),
+            timeout: seconds(self.config.timeout(None)),
+            retries: self.config.retries(None),
+            ..git::Network::default()
+        }
+    }
+
+    /// Create or update the mirror of every remote of a repository
+    ///
+    /// Returns the mirrors by remote name. A mirror that fails to update is skipped as the clone
+    /// can still fetch everything from the remote.
+    fn update_mirrors(&self, repository: &Repository, use_cli: bool) -> Vec<(String, PathBuf)> {
+        let mirror = match self.config.mirror(None) {
+            Some(root) => Mirror::new(root),
+            None => return Vec::new(),
+        };
+
+        let mut references = Vec::new();
+        for remote in &repository.remotes {
+            match mirror.update(&remote.url, use_cli) {
+                Ok(path) => references.push((remote.name.clone(), path)),
+                Err(e) => warn!("failed to update mirror of '{}': {:#}", remote.url, e),
+            }
+        }
+
+        references
+    }
+
+    /// Remove the mirrors that are not used by the remotes of any repository
+    ///
+    /// Repositories hidden by the config filters still keep their mirrors. A mirror that a clone
+    /// in the workspace still borrows objects from is kept, unless the clone is tracked by repo.
+    /// Tracked clones copy the borrowed objects with `git repack` and stop using the mirror
+    /// instead, a clone that fails to do so keeps its mirrors. Nothing is changed if `dry_run` is
+    /// set.
+    ///
+    /// Untracked clones are found by searching the whole workspace root, which reads every folder
+    /// below the root that is not a clone and can take a while on large workspaces.
+    pub fn gc_mirrors(&self, dry_run: bool) -> Result<MirrorGc> {
+        let mirror = match self.config.mirror(None) {
+            Some(root) => Mirror::new(root),
+            None => {
+                return Err(Error::InvalidValue(
+                    "no mirror directory is configured".to_owned(),
+                ))
+            }
+        };
+
+        let used: HashSet<PathBuf> = self
+            .cache
+            .repositories()
+            .iter()
+            .flat_map(|r| r.remotes.iter())
+            .map(|remote| mirror.path(&remote.url))
+            .collect();
+
+        let mut unused: Vec<PathBuf> = mirror
+            .mirrors()?
+            .into_iter()
+            .filter(|path| !used.contains(path))
+            .collect();
+
+        let tracked: HashSet<PathBuf> = self
+            .cache
+            .repositories()
+            .iter()
+            .map(|r| {
+                self.config
+                    .root(None)
+                    .join(r.resolve_workspace_path(&self.cache))
+            })
+            .collect();
+
+        let root = self.config.root(None);
+        let mut clones: Vec<PathBuf> = tracked.iter().filter(|p| p.is_dir()).cloned().collect();
+        if root.is_dir() {
+            clones.extend(git::find_repositories(root)?);
+        }
+        clones.sort();
+        clones.dedup();
+
+        let mut detach = Vec::new();
+        let mut kept = HashSet::new();
+        for clone in clones {
+            let borrowed: Vec<PathBuf> = git::alternates(&clone)?
+                .into_iter()
+                .filter(|path| unused.contains(path))
+                .collect();
+
+            if borrowed.is_empty() {
+                continue;
+            }
+
+            if !tracked.contains(&clone) {
+                for path in borrowed {
+                    info!(
+                        "Keeping mirror: {} as it is used by: {}",
+                        path.display(),
+                        clone.display()
+                    );
+                    kept.insert(path);
+                }
+            } else {
+                detach.push((clone, borrowed));
+            }
+        }
+
+        let mut failed = Vec::new();
+        if !dry_run && !detach.is_empty() {
+            // NOTE: libgit2 cannot repack a repository, so detaching needs git
+            git::cli::check_installed()?;
+
+            for (clone, borrowed) in detach {
+                info!("Detaching: {} from unused mirrors", clone.display());
+                if let Err(e) = git::detach_alternates(&clone, &borrowed) {
+                    warn!("Failed to detach: {}: {:#}", clone.display(), e);
+                    kept.extend(borrowed);
+                    failed.push((clone, e));
                 }
+            }
+        }
+
+        unused.retain(|path| !kept.contains(path));
+        if !dry_run {
+            for path in &unused {
+                mirror.remove(path)?;
+            }
+        }
+
+        Ok(MirrorGc {
+            removed: unused,
+            failed,
+        })
+    }
 
-                let mut command = util::process::piped(program);
-                let status = util::process::execute_command(
-                    command
-                        .args(rest)
-                        .arg(&cmd)
-                        .current_dir(&workspace_path)
-                        .env("REPO_NAME", &repository.name),
-                    repository.name.to_owned(),
-                )
-                .context(format!(
-                    "executing cmd: '{} {} {}' at '{}' failed",
-                    program,
-                    rest.join(" "),
-                    cmd,
-                    workspace_path.display()
-                ))?;
-
-                if !status.success() {
-                    return Err(anyhow!("External command failed: {}", &cmd));
+    /// Execute the clone hooks of a repository and its tags, recording their completion state
+    pub fn run_clone_hooks(&self, repository: &Repository) -> Result<()> {
+        let mut state = State::load(&repository.name)?;
+        state.clone_hooks = Some(HookStatus::Pending);
+        state.write()?;
+
+        let result = self.run_hooks(repository, &repository.clone_hooks(&self.cache));
+
+        state.clone_hooks = Some(if result.is_ok() {
+            HookStatus::Complete
+        } else {
+            HookStatus::Failed
+        });
+        state.write()?;
+
+        result
+    }
+
+    /// Execute hook commands with the configured shell in the repository's workspace path
+    pub fn run_hooks(&self, repository: &Repository, hooks: &[String]) -> Result<()> {
+        let workspace_path = self
+            .config
+            .root(None)
+            .join(repository.resolve_workspace_path(self.cache()));
+
+        if !workspace_path.is_dir() {
+            return Err(Error::NotCloned {
+                name: repository.name.clone(),
+                path: workspace_path,
+            });
+        }
+
+        trace!("path: {:#?}", workspace_path.display());
+
+        let shell = self.config().shell(None);
+        let program = shell.first().ok_or_else(|| {
+            Error::InvalidValue(
+                "'shell' option in configuration must have at least one field".to_owned(),
+            )
+        })?;
+        let rest: &[&str] = shell.split_at(1).1;
+
+        // execute command
+        trace!("Executing commands: {:#?}", hooks);
+        for cmd in hooks {
+            if cmd.is_empty() {
+                return Err(Error::InvalidValue("hook command is empty".to_owned()));
+            }
+
+            let mut command = util::process::piped(program);
+            let status = util::process::execute_command(
+                command
+                    .args(rest)
+                    .arg(cmd)
+                    .current_dir(&workspace_path)
+                    .env("REPO_NAME", &repository.name),
+                repository.name.to_owned(),
+            )
+            .context(format!(
+                "executing cmd: '{} {} {}' at '{}' failed",
+                program,
+                rest.join(" "),
+                cmd,
+                workspace_path.display()
+            ))?;
+
+            if !status.success() {
+                return Err(Error::HookFailed {
+                    command: cmd.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Query the git state of a repository in the workspace
+    ///
+    /// Returns `None` if the repository has not been cloned into the workspace
+    pub fn status(&self, repository: &Repository) -> Result<Option<Status>> {
+        let workspace_path = self
+            .config
+            .root(None)
+            .join(repository.resolve_workspace_path(self.cache()));
+
+        if !workspace_path.is_dir() {
+            return Ok(None);
+        }
+
+        let use_cli = self.use_cli(repository)?;
+        git::status(&workspace_path, use_cli).map(Some)
+    }
+
+    /// Local branches whose upstream no longer exists on the remote
+    ///
+    /// The checked out branch is never included. Returns `None` if the repository has not been
+    /// cloned into the workspace.
+    pub fn gone_branches(&self, repository: &Repository) -> Result<Option<GoneBranches>> {
+        let workspace_path = self
+            .config
+            .root(None)
+            .join(repository.resolve_workspace_path(self.cache()));
+
+        if !workspace_path.is_dir() {
+            return Ok(None);
+        }
+
+        let use_cli = self.use_cli(repository)?;
+        let head = git::status(&workspace_path, use_cli)?.branch;
+
+        let mut gone = GoneBranches::default();
+        for branch in git::gone_branches(&workspace_path, use_cli)? {
+            if Some(&branch) == head.as_ref() {
+                continue;
+            }
+
+            if git::is_merged(&workspace_path, &branch, use_cli)? {
+                gone.merged.push(branch);
+            } else {
+                gone.unmerged.push(branch);
+            }
+        }
+
+        Ok(Some(gone))
+    }
+
+    /// Delete local branches of a clone, a branch that is not merged into the checked out branch
+    /// is not deleted
+    pub fn delete_branches(&self, repository: &Repository, branches: &[String]) -> Result<()> {
+        let workspace_path = self
+            .config
+            .root(None)
+            .join(repository.resolve_workspace_path(self.cache()));
+
+        let use_cli = self.use_cli(repository)?;
+        for branch in branches {
+            git::delete_branch(&workspace_path, branch, use_cli)?;
+        }
+
+        Ok(())
+    }
+
+    /// Create a repository from a git repository that already exists on disk
+    ///
+    /// The name of the repository is the name of its folder and the remotes are read from the git
+    /// configuration, with `origin` first. The path of the repository is its folder relative to the
+    /// workspace root. Clones outside of the root are rejected, an absolute path would tie the
+    /// config to a single machine.
+    pub fn repository_from_clone(&self, path: &Path) -> Result<RepositoryBuilder> {
+        let path = util::canonicalize(path)?;
+        let name = path.file_name().and_then(|n| n.to_str()).ok_or_else(|| {
+            Error::InvalidValue(format!(
+                "failed to get repository name from: {}",
+                path.display()
+            ))
+        })?;
+
+        let mut builder = RepositoryBuilder::new(name);
+
+        let parent = path.parent().unwrap_or(&path);
+        let root = util::canonicalize(self.config.root(None)).unwrap_or_default();
+        match parent.strip_prefix(&root) {
+            Ok(relative) if relative.as_os_str().is_empty() => {}
+            Ok(relative) => builder = builder.path(relative),
+            Err(_) => {
+                return Err(Error::OutsideRoot {
+                    path: path.clone(),
+                    root,
+                })
+            }
+        }
+
+        // By convention the first remote is the default remote, so make sure 'origin' comes first
+        let mut remotes = git::libgit::remotes(&path)?;
+        remotes.sort_by_key(|(remote_name, _)| remote_name != "origin");
+
+        for (remote_name, url) in remotes {
+            match Query::parse(&url).and_then(|query| query.to_url(&self.config)) {
+                Ok(url) => builder = builder.remote(Remote::with_name(&remote_name, url)),
+                Err(e) => warn!("skipping remote '{}' of '{}': {:#}", remote_name, name, e),
+            }
+        }
+
+        Ok(builder)
+    }
+
+    /// Apply the changes of a manifest plan to the cache, writing them to disk
+    pub fn apply_plan(&mut self, plan: Plan) -> Result<()> {
+        for change in plan.tags {
+            match change {
+                Change::Add(tag) => {
+                    self.write_tag(&tag)?;
+                    self.cache.add_tag(tag);
                 }
+                Change::Update { old, new } => {
+                    if old.config != new.config {
+                        old.del_cache_file()?;
+                    }
+                    self.write_tag(&new)?;
+                    self.cache.add_tag(new);
+                }
+                Change::Remove(tag) => self.remove_tag(&tag.name)?,
+            }
+        }
+
+        for change in plan.repositories {
+            match change {
+                Change::Add(repository) => {
+                    self.write_repository(&repository)?;
+                    self.cache.add_repository(repository);
+                }
+                Change::Update { old, new } => {
+                    if old.config != new.config {
+                        old.del_cache_file()?;
+                    }
+                    self.write_repository(&new)?;
+                    self.cache.add_repository(new);
+                }
+                Change::Remove(repository) => self.remove_repository(&repository.name)?,
             }
         }
 
@@ -215,7 +910,6 @@ impl Workspace {
         util::write_content(file, |f| {
             f.write_fmt(format_args!("{}", ser))
                 .context(format!("failed to write file: {:#?}", file))
-                .map_err(Into::into)
         })
     }
 
@@ -229,7 +923,177 @@ impl Workspace {
         util::write_content(file, |f| {
             f.write_fmt(format_args!("{}", ser))
                 .context(format!("failed to write file: {:#?}", file))
-                .map_err(Into::into)
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::{bare_repository, TempDir};
+
+    fn workspace(root: &Path) -> Workspace {
+        let mut config = Config::default();
+        config.set_root(&root.display().to_string(), root.to_path_buf(), None);
+        Workspace {
+            config,
+            cache: Cache::default(),
+        }
+    }
+
+    fn clone_at(origin: &Path, path: &Path) -> git2::Repository {
+        let repo = git2::Repository::init(path).unwrap();
+        repo.remote("origin", &origin.display().to_string())
+            .unwrap();
+        repo
+    }
+
+    #[test]
+    fn clone_name_and_path() {
+        let dir = TempDir::new();
+        let (_, origin) = bare_repository(&dir);
+        let root = dir.0.join("root");
+        clone_at(&origin, &root.join("top"));
+        clone_at(&origin, &root.join("group/nested/inner"));
+
+        let workspace = workspace(&root);
+        let repository = workspace
+            .repository_from_clone(&root.join("top"))
+            .unwrap()
+            .build();
+        assert_eq!(repository.name, "top");
+        assert_eq!(repository.path, None);
+
+        let repository = workspace
+            .repository_from_clone(&root.join("group/nested/inner"))
+            .unwrap()
+            .build();
+        assert_eq!(repository.name, "inner");
+        assert_eq!(repository.path, Some(PathBuf::from("group/nested")));
+    }
+
+    #[test]
+    fn clone_origin_first() {
+        let dir = TempDir::new();
+        let (_, origin) = bare_repository(&dir);
+        let root = dir.0.join("root");
+        let path = root.join("clone");
+        let repo = git2::Repository::init(&path).unwrap();
+        repo.remote("upstream", &origin.display().to_string())
+            .unwrap();
+        repo.remote("origin", &origin.display().to_string())
+            .unwrap();
+
+        let repository = workspace(&root)
+            .repository_from_clone(&path)
+            .unwrap()
+            .build();
+        let names: Vec<&str> = repository.remotes.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["origin", "upstream"]);
+    }
+
+    #[test]
+    fn clone_outside_root() {
+        let dir = TempDir::new();
+        let (_, origin) = bare_repository(&dir);
+        let root = dir.0.join("root");
+        std::fs::create_dir_all(&root).unwrap();
+        let path = dir.0.join("elsewhere/clone");
+        clone_at(&origin, &path);
+
+        match workspace(&root).repository_from_clone(&path) {
+            Err(Error::OutsideRoot { path: outside, .. }) => {
+                assert_eq!(outside, util::canonicalize(&path).unwrap())
+            }
+            result => panic!(
+                "expected outside of root error, found: {:?}",
+                result.map(|_| ())
+            ),
+        }
+    }
+
+    #[test]
+    fn alias_of_repository_name() {
+        let dir = TempDir::new();
+        let mut workspace = workspace(&dir.0);
+        workspace
+            .cache
+            .add_repository(RepositoryBuilder::new("cargo").build());
+
+        let repository = RepositoryBuilder::new("repo")
+            .alias("cargo".to_owned())
+            .build();
+        assert!(workspace.check_aliases(&repository).is_err());
+        assert!(workspace.add_repository(repository).is_err());
+
+        let repository = RepositoryBuilder::new("repo")
+            .alias("rp".to_owned())
+            .alias("repo".to_owned())
+            .build();
+        assert!(workspace.check_aliases(&repository).is_ok());
+    }
+
+    /// Workspace with a tracked clone borrowing from a mirror that no repository uses anymore
+    fn unused_mirror(dir: &TempDir) -> (Workspace, PathBuf) {
+        let (_, origin) = bare_repository(dir);
+        let url = url::Url::from_file_path(&origin).unwrap();
+        let mirror_root = dir.0.join("mirror");
+        let reference = Mirror::new(&mirror_root).path(&url);
+        git::mirror(&reference, url.as_str(), false).unwrap();
+
+        let mut workspace = workspace(&dir.0.join("root"));
+        workspace.config.set_mirror(
+            Some((&mirror_root.display().to_string(), mirror_root.clone())),
+            None,
+        );
+
+        let other = url::Url::parse("https://example.com/other.git").unwrap();
+        workspace.cache.add_repository(
+            RepositoryBuilder::new("clone")
+                .remote(Remote::new(other))
+                .build(),
+        );
+
+        (workspace, reference)
+    }
+
+    #[test]
+    fn gc_detaches_tracked_clones() {
+        let dir = TempDir::new();
+        let (workspace, reference) = unused_mirror(&dir);
+        let clone = dir.0.join("root/clone");
+        let url = url::Url::from_file_path(dir.0.join("origin.git")).unwrap();
+        let options = git::CloneOptions {
+            references: vec![("origin".to_owned(), reference.clone())],
+            ..Default::default()
+        };
+        git::clone(&clone, &[Remote::new(url)], &options, false).unwrap();
+
+        let gc = workspace.gc_mirrors(false).unwrap();
+        assert_eq!(gc.removed, vec![reference.clone()]);
+        assert!(gc.failed.is_empty());
+        assert!(!reference.exists());
+        assert!(git::alternates(&clone).unwrap().is_empty());
+    }
+
+    #[test]
+    fn gc_keeps_mirrors_of_failed_clones() {
+        let dir = TempDir::new();
+        let (workspace, reference) = unused_mirror(&dir);
+
+        // A clone without any git data borrowing from the mirror, repacking it fails
+        let info = dir.0.join("root/clone/.git/objects/info");
+        std::fs::create_dir_all(&info).unwrap();
+        std::fs::write(
+            info.join("alternates"),
+            format!("{}\n", reference.join("objects").display()),
+        )
+        .unwrap();
+
+        let gc = workspace.gc_mirrors(false).unwrap();
+        assert!(gc.removed.is_empty());
+        assert_eq!(gc.failed.len(), 1);
+        assert_eq!(gc.failed[0].0, dir.0.join("root/clone"));
+        assert!(reference.exists());
+    }
+}